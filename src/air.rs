@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::fields;
+
+/// Air left to inflate the balloon, the default tank holds 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AirTank {
+    pub amount: f32,
+    pub capacity: f32,
+}
+
+impl AirTank {
+    /// Levels start with an empty tank
+    pub fn empty(capacity: f32) -> Self {
+        Self {
+            amount: 0.0,
+            capacity,
+        }
+    }
+
+    pub fn full(capacity: f32) -> Self {
+        Self {
+            amount: capacity,
+            capacity,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.amount >= self.capacity
+    }
+
+    /// Use `air`, false if the tank was already empty
+    pub fn take(&mut self, air: f32) -> bool {
+        let remained = self.amount > 0.0;
+        self.amount = (self.amount - air).max(0.0);
+        remained
+    }
+
+    /// Lose `rate` air per second
+    pub fn leak(&mut self, rate: f32, tick: f32) {
+        self.amount = (self.amount - rate * tick).max(0.0);
+    }
+
+    /// Add the air of `refill`, false if the tank is full and the capacity stays the same
+    pub fn refill(&mut self, refill: &Refill) -> bool {
+        if self.is_full() && refill.capacity <= 0.0 {
+            return false;
+        }
+        self.capacity += refill.capacity;
+        let amount = refill.amount.unwrap_or(self.capacity);
+        self.amount = (self.amount + amount).min(self.capacity);
+        true
+    }
+}
+
+/// Air given by an `Inflator`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Refill {
+    /// `None` fills the tank
+    pub amount: Option<f32>,
+    /// Raise of the tank capacity
+    pub capacity: f32,
+    /// Seconds before the pump can be used again, it is used once if 0
+    pub cooldown: f32,
+}
+
+impl Refill {
+    /// Read the LDtk fields `Amount`, `Capacity` and `Cooldown`
+    pub fn from_settings(settings: &Value) -> Self {
+        let field = |name| fields::float(settings, name).map(|v| v.max(0.0));
+        Self {
+            amount: field("Amount"),
+            capacity: field("Capacity").unwrap_or_default(),
+            cooldown: field("Cooldown").unwrap_or_default(),
+        }
+    }
+}
+
+/// An `Inflator`, it can't be used while it cools down
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pump {
+    pub refill: Refill,
+    timer: f32,
+}
+
+impl Pump {
+    pub fn new(refill: Refill) -> Self {
+        Self { refill, timer: 0.0 }
+    }
+
+    /// Whether it is used again after its cooldown instead of being picked up
+    pub fn reusable(&self) -> bool {
+        self.refill.cooldown > 0.0
+    }
+
+    pub fn ready(&self) -> bool {
+        self.timer <= 0.0
+    }
+
+    /// Refill `tank`, the capacity is only raised if the pump hasn't `upgraded` it before
+    ///
+    /// Returns the raise of the capacity once the pump is used.
+    pub fn use_on(&mut self, tank: &mut AirTank, upgraded: bool) -> Option<f32> {
+        let mut refill = self.refill;
+        if upgraded {
+            refill.capacity = 0.0;
+        }
+        if !self.ready() || !tank.refill(&refill) {
+            return None;
+        }
+        self.timer = refill.cooldown;
+        Some(refill.capacity)
+    }
+
+    /// Cool down, true once the pump is ready again
+    pub fn update(&mut self, tick: f32) -> bool {
+        if self.ready() {
+            return false;
+        }
+        self.timer -= tick;
+        self.ready()
+    }
+}
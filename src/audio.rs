@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use roast_2d::{ldtk::LdtkLevel, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{read_storage, write_storage};
+
+const AUDIO_KEY: &str = "audio";
+/// Level field naming the music of a level, a file in `sounds/music`
+const MUSIC_FIELD: &str = "Music";
+pub const DEFAULT_MUSIC: &str = "float.wav";
+/// Change of a volume per step in the settings
+pub const VOLUME_STEP: f32 = 0.1;
+/// Speed of the balloon against a wall from which it is heard, sum of both axes
+pub const IMPACT_MIN_SPEED: f32 = 120.0;
+/// Speed of the loudest impact
+const IMPACT_FULL_SPEED: f32 = 480.0;
+
+/// Mixer track a sound plays on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    Master,
+    Sfx,
+    Music,
+}
+
+impl Bus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Bus::Master => "Master",
+            Bus::Sfx => "SFX",
+            Bus::Music => "Music",
+        }
+    }
+}
+
+/// Volumes chosen in the settings, from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            sfx: 1.0,
+            music: 0.7,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn volume(&self, bus: Bus) -> f32 {
+        match bus {
+            Bus::Master => self.master,
+            Bus::Sfx => self.sfx,
+            Bus::Music => self.music,
+        }
+    }
+
+    pub fn set_volume(&mut self, bus: Bus, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match bus {
+            Bus::Master => self.master = volume,
+            Bus::Sfx => self.sfx = volume,
+            Bus::Music => self.music = volume,
+        }
+    }
+
+    /// Move the volume of `bus` by `steps` of `VOLUME_STEP`
+    pub fn step(&mut self, bus: Bus, steps: i32) {
+        let step = (self.volume(bus) / VOLUME_STEP).round() as i32 + steps;
+        self.set_volume(bus, step as f32 * VOLUME_STEP);
+    }
+
+    /// Volume of the main track, muting silences every bus at once
+    pub fn master_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master
+        }
+    }
+
+    pub fn load() -> Self {
+        let Some(data) = read_storage(AUDIO_KEY) else {
+            return Self::default();
+        };
+        match serde_json::from_str(&data) {
+            Ok(settings) => settings,
+            Err(err) => {
+                log::error!("Can't read audio settings err {err:?}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let res = serde_json::to_string(self)
+            .map_err(Error::from)
+            .and_then(|data| write_storage(AUDIO_KEY, &data));
+        if let Err(err) = res {
+            log::error!("Can't write audio settings err {err:?}");
+        }
+    }
+}
+
+/// Path of the music of `level`, `None` if the level is silent
+pub fn level_music(level: &LdtkLevel) -> Option<String> {
+    let name = match level.get_field(MUSIC_FIELD).map(|field| &field.value) {
+        Some(Value::String(name)) => name.as_str(),
+        _ => DEFAULT_MUSIC,
+    };
+    (!name.is_empty()).then(|| format!("sounds/music/{name}"))
+}
+
+/// Sounds of a game event, one of the files plays at random
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SoundBank {
+    pub files: Vec<String>,
+    #[serde(default = "full_volume")]
+    pub volume: f32,
+    /// Playback rate is picked in this range
+    #[serde(default = "normal_rate")]
+    pub rate: [f32; 2],
+    /// Seconds repeated while the sound keeps playing
+    #[serde(default)]
+    pub loop_region: Option<[f64; 2]>,
+}
+
+fn full_volume() -> f32 {
+    1.0
+}
+
+fn normal_rate() -> [f32; 2] {
+    [1.0, 1.0]
+}
+
+/// Mapping of event names to their sounds, read from `assets/sounds.json`
+pub fn parse_sound_banks(data: &[u8]) -> Result<HashMap<String, SoundBank>> {
+    Ok(serde_json::from_slice(data)?)
+}
+
+/// Volume factor of an impact at `speed`, faint at `IMPACT_MIN_SPEED`
+pub fn impact_volume(speed: f32) -> f32 {
+    let t = (speed - IMPACT_MIN_SPEED) / (IMPACT_FULL_SPEED - IMPACT_MIN_SPEED);
+    0.3 + 0.7 * t.clamp(0.0, 1.0)
+}
+
+/// Pitch factor of an impact, `size` goes from 0 deflated to 1 fully inflated
+pub fn impact_rate(size: f32) -> f32 {
+    1.4 - 0.8 * size.clamp(0.0, 1.0)
+}
+
+/// Stereo panning of a sound at `x`, from 0 on the left edge of the view to 1 on the right one
+///
+/// The camera centers `target_x` unless it would show past the sides of the level.
+pub fn stereo_panning(x: f32, target_x: f32, view_width: f32, level_width: f32) -> f32 {
+    let half = view_width * 0.5;
+    let left = (target_x - half).clamp(0.0, (level_width - view_width).max(0.0));
+    ((x - left) / view_width).clamp(0.0, 1.0)
+}
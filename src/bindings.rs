@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use roast_2d::{input::InputState, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{read_storage, write_storage, Action};
+
+const BINDINGS_KEY: &str = "bindings";
+/// roast-2d only reports bound keys, captured keys are bound to ids above every `Action`
+const RAW_KEY_BASE: u8 = u8::MAX - KeyCode::KeyMax as u8;
+
+/// Result of binding a key to an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rebind {
+    Added,
+    /// The key already belonged to the action
+    Unchanged,
+    /// The key was taken from another action
+    Moved(Action),
+    /// The key is the last one of another action and was left there
+    Conflict(Action),
+}
+
+/// Keys of every action, several keys can trigger the same action but a key triggers one action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bindings {
+    /// Key codes of each action, stored as numbers since `KeyCode` isn't serializable
+    keys: BTreeMap<Action, Vec<u8>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let mut bindings = Self {
+            keys: BTreeMap::new(),
+        };
+        for (key, action) in [
+            (KeyCode::Left, Action::Left),
+            (KeyCode::Right, Action::Right),
+            (KeyCode::KeyA, Action::Left),
+            (KeyCode::KeyD, Action::Right),
+            (KeyCode::Up, Action::Up),
+            (KeyCode::KeyW, Action::Up),
+            (KeyCode::Down, Action::Down),
+            (KeyCode::KeyS, Action::Down),
+            (KeyCode::Space, Action::Jump),
+            (KeyCode::KeyI, Action::Inflate),
+            (KeyCode::KeyO, Action::Deflate),
+            (KeyCode::KeyR, Action::Restart),
+            (KeyCode::Return, Action::Confirm),
+            (KeyCode::KeyG, Action::ToggleGhost),
+            (KeyCode::Escape, Action::Pause),
+            (KeyCode::KeyP, Action::Pause),
+            (KeyCode::GamepadLStickLeft, Action::Left),
+            (KeyCode::GamepadLStickRight, Action::Right),
+            (KeyCode::GamepadLStickUp, Action::Up),
+            (KeyCode::GamepadLStickDown, Action::Down),
+            (KeyCode::GamepadDpadLeft, Action::Left),
+            (KeyCode::GamepadDpadRight, Action::Right),
+            (KeyCode::GamepadDpadUp, Action::Up),
+            (KeyCode::GamepadDpadDown, Action::Down),
+            (KeyCode::GamepadA, Action::Jump),
+            (KeyCode::GamepadRTrigger, Action::Inflate),
+            (KeyCode::GamepadLTrigger, Action::Deflate),
+            (KeyCode::GamepadRShoulder, Action::Inflate),
+            (KeyCode::GamepadLShoulder, Action::Deflate),
+            (KeyCode::GamepadY, Action::Restart),
+            (KeyCode::GamepadX, Action::Confirm),
+            (KeyCode::GamepadSelect, Action::ToggleGhost),
+            (KeyCode::GamepadStart, Action::Pause),
+        ] {
+            bindings.bind(action, key);
+        }
+        bindings
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> Vec<KeyCode> {
+        self.keys
+            .get(&action)
+            .map(|keys| keys.iter().map(|&code| KeyCode::from(code)).collect())
+            .unwrap_or_default()
+    }
+
+    /// The action triggered by `key`
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(_, keys)| keys.contains(&(key as u8)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Add `key` to the keys of `action`, taking it from the action that had it
+    pub fn bind(&mut self, action: Action, key: KeyCode) -> Rebind {
+        let code = key as u8;
+        let res = match self.action(key) {
+            Some(other) if other == action => return Rebind::Unchanged,
+            Some(other) if self.keys[&other].len() == 1 => return Rebind::Conflict(other),
+            Some(other) => {
+                self.keys.entry(other).or_default().retain(|&c| c != code);
+                Rebind::Moved(other)
+            }
+            None => Rebind::Added,
+        };
+        self.keys.entry(action).or_default().push(code);
+        res
+    }
+
+    /// Remove the last key of `action`, an action always keeps one key
+    pub fn unbind_last(&mut self, action: Action) -> Option<KeyCode> {
+        let keys = self.keys.get_mut(&action)?;
+        if keys.len() < 2 {
+            return None;
+        }
+        keys.pop().map(KeyCode::from)
+    }
+
+    /// Replace the bindings of `input`
+    pub fn apply(&self, input: &mut InputState) {
+        input.unbind_all();
+        for (action, keys) in &self.keys {
+            for &code in keys {
+                input.bind(KeyCode::from(code), *action);
+            }
+        }
+    }
+
+    /// Give the default keys to actions added since the bindings were saved, and the default
+    /// gamepad buttons to actions saved without any, unless another action took them
+    pub fn with_defaults(mut self) -> Self {
+        for (action, defaults) in Self::default().keys {
+            let Some(keys) = self.keys.get(&action) else {
+                self.keys.insert(action, defaults);
+                continue;
+            };
+            if keys.iter().any(|&code| is_gamepad(code)) {
+                continue;
+            }
+            for code in defaults.into_iter().filter(|&code| is_gamepad(code)) {
+                if self.action(KeyCode::from(code)).is_none() {
+                    self.keys.entry(action).or_default().push(code);
+                }
+            }
+        }
+        self
+    }
+
+    pub fn load() -> Self {
+        let Some(data) = read_storage(BINDINGS_KEY) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&data) {
+            Ok(bindings) => bindings.with_defaults(),
+            Err(err) => {
+                log::error!("Can't read key bindings err {err:?}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let res = serde_json::to_string(self)
+            .map_err(Error::from)
+            .and_then(|data| write_storage(BINDINGS_KEY, &data));
+        if let Err(err) = res {
+            log::error!("Can't write key bindings err {err:?}");
+        }
+    }
+}
+
+fn is_gamepad(code: u8) -> bool {
+    (KeyCode::GamepadA as u8..=KeyCode::GamepadRStickRight as u8).contains(&code)
+}
+
+/// Display name of a key, e.g. `A` for `KeyA`
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    match name.strip_prefix("Key") {
+        Some(letter) if !letter.is_empty() => letter.to_string(),
+        _ => name,
+    }
+}
+
+/// Keys a player can bind
+pub fn bindable_keys() -> impl Iterator<Item = KeyCode> {
+    (1..KeyCode::KeyMax as u8)
+        .map(KeyCode::from)
+        .filter(|key| !matches!(key, KeyCode::Invalid | KeyCode::Max))
+}
+
+/// Id reporting the state of `key` while keys are captured
+pub fn raw_key(key: KeyCode) -> ActionId {
+    ActionId(RAW_KEY_BASE + key as u8)
+}
+
+/// Bind every key to its raw id instead of the actions, until the bindings are applied again
+pub fn capture_keys(input: &mut InputState) {
+    input.unbind_all();
+    for key in bindable_keys() {
+        input.bind(key, raw_key(key));
+    }
+}
@@ -0,0 +1,51 @@
+use roast_2d::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::fields;
+
+/// What happens to an entity consumed before a checkpoint when the player respawns there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RespawnPolicy {
+    /// The entity stays consumed
+    Keep,
+    /// The entity comes back
+    Reset,
+}
+
+impl RespawnPolicy {
+    /// Read the LDtk field `Respawn` (Keep or Reset), entities stay consumed by default
+    pub fn from_settings(settings: &Value) -> Self {
+        fields::variant(
+            settings,
+            "Respawn",
+            &[
+                ("Keep", RespawnPolicy::Keep),
+                ("Reset", RespawnPolicy::Reset),
+            ],
+            RespawnPolicy::Keep,
+        )
+    }
+}
+
+/// The player when it touched a checkpoint, restored when it respawns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Respawn {
+    /// LDtk iid of the checkpoint
+    pub checkpoint: String,
+    pub pos: [f32; 2],
+    pub inflation: f32,
+    /// Size of the balloon grown by crowns, the player size if `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_size: Option<[f32; 2]>,
+    /// The capacity comes from the upgrades, they are kept on death
+    pub air: f32,
+    /// LDtk iids of the entities consumed before the checkpoint that stay consumed
+    pub removed: Vec<String>,
+}
+
+impl Respawn {
+    pub fn pos(&self) -> Vec2 {
+        Vec2::from(self.pos)
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+
+use roast_2d::prelude::*;
+
+use crate::Body;
+
+/// Why the player died
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeathCause {
+    Spikes,
+    /// The balloon burst from pressure or on a sharp tile
+    Popped,
+    /// The balloon left the level
+    OutOfBounds,
+    Restart,
+}
+
+impl DeathCause {
+    pub const ALL: [DeathCause; 4] = [
+        DeathCause::Spikes,
+        DeathCause::Popped,
+        DeathCause::OutOfBounds,
+        DeathCause::Restart,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DeathCause::Spikes => "Spikes",
+            DeathCause::Popped => "Popped",
+            DeathCause::OutOfBounds => "Out of bounds",
+            DeathCause::Restart => "Restart",
+        }
+    }
+
+    /// Seconds of the death animation before the level reloads, a restart is asked for and quicker
+    pub fn duration(self) -> f32 {
+        match self {
+            DeathCause::Restart => 0.3,
+            _ => 0.8,
+        }
+    }
+}
+
+/// Life of the player in the current level
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DeathState {
+    #[default]
+    Alive,
+    /// The death animation plays, the level reloads once it is over
+    Dying { cause: DeathCause, timer: f32 },
+}
+
+impl DeathState {
+    /// Start dying, false if the player is already dying
+    pub fn kill(&mut self, cause: DeathCause) -> bool {
+        if *self != DeathState::Alive {
+            return false;
+        }
+        *self = DeathState::Dying { cause, timer: 0.0 };
+        true
+    }
+
+    pub fn cause(&self) -> Option<DeathCause> {
+        match self {
+            DeathState::Alive => None,
+            DeathState::Dying { cause, .. } => Some(*cause),
+        }
+    }
+
+    /// Share of the death animation played, 0 while alive
+    pub fn progress(&self) -> f32 {
+        match self {
+            DeathState::Alive => 0.0,
+            DeathState::Dying { cause, timer } => (timer / cause.duration()).min(1.0),
+        }
+    }
+
+    /// Advance the animation, the cause is returned once the level has to reload
+    pub fn update(&mut self, tick: f32) -> Option<DeathCause> {
+        let DeathState::Dying { cause, timer } = self else {
+            return None;
+        };
+        *timer += tick;
+        if *timer < cause.duration() {
+            return None;
+        }
+        let cause = *cause;
+        *self = DeathState::Alive;
+        Some(cause)
+    }
+}
+
+/// Deaths of each level by cause
+#[derive(Debug, Clone, Default)]
+pub struct DeathTally {
+    levels: HashMap<String, BTreeMap<DeathCause, usize>>,
+}
+
+impl DeathTally {
+    pub fn record(&mut self, level: &str, cause: DeathCause) {
+        *self
+            .levels
+            .entry(level.to_string())
+            .or_default()
+            .entry(cause)
+            .or_default() += 1;
+    }
+
+    pub fn count(&self, level: &str, cause: DeathCause) -> usize {
+        self.levels
+            .get(level)
+            .and_then(|causes| causes.get(&cause))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Deaths of `level` by cause, causes without deaths are left out
+    pub fn level(&self, level: &str) -> Vec<(DeathCause, usize)> {
+        self.levels
+            .get(level)
+            .map(|causes| causes.iter().map(|(cause, n)| (*cause, *n)).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Whether `body` is entirely outside a level of `bounds` pixels
+pub fn out_of_bounds(body: &Body, bounds: Vec2) -> bool {
+    let rect = body.bounds();
+    rect.max.x < 0.0 || rect.max.y < 0.0 || rect.min.x > bounds.x || rect.min.y > bounds.y
+}
@@ -0,0 +1,102 @@
+use roast_2d::prelude::Engine;
+
+/// A collision of the balloon loud enough to be heard
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Impact {
+    /// Sum of the speeds on both axes
+    pub speed: f32,
+    /// Inflation of the balloon, 0 deflated and 1 fully inflated
+    pub size: f32,
+    /// Stereo position of the contact, 0 left and 1 right
+    pub panning: f32,
+}
+
+impl Impact {
+    pub const CENTER: Impact = Impact {
+        speed: 0.0,
+        size: 0.0,
+        panning: 0.5,
+    };
+}
+
+/// Something that happened in the world, systems like the sound react to it at the end of the frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    PlayerDied,
+    /// The player died by bursting the balloon
+    Popped,
+    ItemPickedUp,
+    CheckpointReached,
+    DoorEntered,
+    ButtonPressed,
+    SpikesRemoved,
+    /// The balloon hit a wall fast enough to be heard
+    Bounced(Impact),
+    Inflating,
+    Deflating,
+    /// The balloon neither inflates nor deflates
+    AirStopped,
+}
+
+impl GameEvent {
+    pub const ALL: [GameEvent; 11] = [
+        GameEvent::PlayerDied,
+        GameEvent::Popped,
+        GameEvent::ItemPickedUp,
+        GameEvent::CheckpointReached,
+        GameEvent::DoorEntered,
+        GameEvent::ButtonPressed,
+        GameEvent::SpikesRemoved,
+        GameEvent::Bounced(Impact::CENTER),
+        GameEvent::Inflating,
+        GameEvent::Deflating,
+        GameEvent::AirStopped,
+    ];
+
+    /// Key of the event in the sound mapping
+    pub fn name(self) -> &'static str {
+        match self {
+            GameEvent::PlayerDied => "PlayerDied",
+            GameEvent::Popped => "Popped",
+            GameEvent::ItemPickedUp => "ItemPickedUp",
+            GameEvent::CheckpointReached => "CheckpointReached",
+            GameEvent::DoorEntered => "DoorEntered",
+            GameEvent::ButtonPressed => "ButtonPressed",
+            GameEvent::SpikesRemoved => "SpikesRemoved",
+            GameEvent::Bounced(_) => "Bounced",
+            GameEvent::Inflating => "Inflating",
+            GameEvent::Deflating => "Deflating",
+            GameEvent::AirStopped => "AirStopped",
+        }
+    }
+}
+
+/// Events emitted during a frame
+#[derive(Default)]
+pub struct EventBus {
+    events: Vec<GameEvent>,
+}
+
+impl EventBus {
+    pub fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// Take the events of the frame, each kind at most once and in the order they first happened
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        let mut events = std::mem::take(&mut self.events);
+        let mut seen = Vec::with_capacity(events.len());
+        events.retain(|event| {
+            let kind = std::mem::discriminant(event);
+            let new = !seen.contains(&kind);
+            seen.push(kind);
+            new
+        });
+        events
+    }
+}
+
+/// A system reacting to the events of the world
+pub trait EventListener {
+    fn on_event(&mut self, eng: &Engine, event: GameEvent);
+}
@@ -0,0 +1,81 @@
+use roast_2d::prelude::*;
+use serde_json::Value;
+
+use crate::{fields, Body, PLAYER_SIZE};
+
+/// Length of the blown region when the level doesn't set it
+const DEFAULT_LENGTH: f32 = 160.0;
+const DEFAULT_WIDTH: f32 = 32.0;
+const DEFAULT_FORCE: f32 = 300.0;
+
+/// Region blown by a `Fan`, it starts at the fan and reaches `Length` pixels in its `Direction`
+#[derive(Debug, Clone)]
+pub struct Wind {
+    pub region: Rect,
+    pub dir: Vec2,
+    /// Acceleration of a balloon of the player's original size
+    pub force: f32,
+    /// Switches turn the fan on and off
+    pub blowing: bool,
+}
+
+impl Wind {
+    pub fn new(pos: Vec2, dir: Vec2, length: f32, width: f32, force: f32) -> Self {
+        let side = dir.perp() * width * 0.5;
+        let (a, b) = (pos + side, pos + dir * length - side);
+        Self {
+            region: Rect {
+                min: a.min(b),
+                max: a.max(b),
+            },
+            dir,
+            force,
+            blowing: true,
+        }
+    }
+
+    /// Read the LDtk fields `Direction` (Up, Down, Left or Right), `Length`, `Width`, `Force`
+    /// and `Blowing`
+    pub fn from_settings(pos: Vec2, settings: &Value) -> Self {
+        let dir = fields::variant(
+            settings,
+            "Direction",
+            &[
+                ("Up", Vec2::NEG_Y),
+                ("Down", Vec2::Y),
+                ("Left", Vec2::NEG_X),
+                ("Right", Vec2::X),
+            ],
+            Vec2::NEG_Y,
+        );
+        let field = |name, default| fields::float(settings, name).unwrap_or(default);
+        Self {
+            blowing: fields::boolean(settings, "Blowing").unwrap_or(true),
+            ..Self::new(
+                pos,
+                dir,
+                field("Length", DEFAULT_LENGTH),
+                field("Width", DEFAULT_WIDTH),
+                field("Force", DEFAULT_FORCE),
+            )
+        }
+    }
+
+    /// Acceleration of `body`, the wind pushes the side facing it so bigger balloons fly further
+    pub fn accel(&self, body: &Body) -> Vec2 {
+        let bounds = body.bounds();
+        let inside = bounds.min.x < self.region.max.x
+            && bounds.max.x > self.region.min.x
+            && bounds.min.y < self.region.max.y
+            && bounds.max.y > self.region.min.y;
+        if !self.blowing || !inside {
+            return Vec2::ZERO;
+        }
+        let cross_section = if self.dir.x != 0.0 {
+            body.size.y / PLAYER_SIZE.y
+        } else {
+            body.size.x / PLAYER_SIZE.x
+        };
+        self.dir * self.force * cross_section
+    }
+}
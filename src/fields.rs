@@ -0,0 +1,28 @@
+//! Optional fields of LDtk entities, as passed to `EntityType::settings`
+
+use serde_json::Value;
+
+pub fn float(settings: &Value, name: &str) -> Option<f32> {
+    settings
+        .get(name)
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32)
+}
+
+pub fn boolean(settings: &Value, name: &str) -> Option<bool> {
+    settings.get(name).and_then(|v| v.as_bool())
+}
+
+/// The variant named by the enum field `name`, `default` if unset or unknown
+pub fn variant<T: Copy>(settings: &Value, name: &str, variants: &[(&str, T)], default: T) -> T {
+    let Some(value) = settings.get(name).and_then(|v| v.as_str()) else {
+        return default;
+    };
+    match variants.iter().find(|(id, _)| *id == value) {
+        Some((_, variant)) => *variant,
+        None => {
+            log::error!("Unknown {name} {value}");
+            default
+        }
+    }
+}
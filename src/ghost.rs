@@ -0,0 +1,65 @@
+use roast_2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{read_storage, write_storage};
+
+/// Seconds between two samples, positions in between are interpolated
+pub const GHOST_INTERVAL: f32 = 1.0 / 30.0;
+
+/// Trajectory of the player through a level, sampled at a fixed rate so it replays at any frame rate
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GhostRun {
+    /// Completion time of the run
+    pub time: f32,
+    /// Center and size of the balloon, `[x, y, width, height]`
+    samples: Vec<[f32; 4]>,
+}
+
+impl GhostRun {
+    /// Record the balloon at `time` seconds into the level
+    pub fn record(&mut self, time: f32, pos: Vec2, size: Vec2) {
+        while self.samples.len() as f32 * GHOST_INTERVAL <= time {
+            self.samples.push([pos.x, pos.y, size.x, size.y]);
+        }
+    }
+
+    /// Center and size at `time`, `None` once the run is over
+    pub fn sample(&self, time: f32) -> Option<(Vec2, Vec2)> {
+        let index = (time / GHOST_INTERVAL) as usize;
+        let [x, y, w, h] = *self.samples.get(index)?;
+        let (pos, size) = (Vec2::new(x, y), Vec2::new(w, h));
+        let Some(&[nx, ny, nw, nh]) = self.samples.get(index + 1) else {
+            return Some((pos, size));
+        };
+        let t = time / GHOST_INTERVAL - index as f32;
+        Some((
+            pos.lerp(Vec2::new(nx, ny), t),
+            size.lerp(Vec2::new(nw, nh), t),
+        ))
+    }
+}
+
+fn ghost_key(slot: usize, level: &str) -> String {
+    format!("ghost_{slot}_{level}")
+}
+
+/// The best completion of `level` in a save slot
+pub fn load_ghost(slot: usize, level: &str) -> Option<GhostRun> {
+    let data = read_storage(&ghost_key(slot, level))?;
+    match serde_json::from_str(&data) {
+        Ok(run) => Some(run),
+        Err(err) => {
+            log::error!("Can't read ghost of {level} err {err:?}");
+            None
+        }
+    }
+}
+
+pub fn save_ghost(slot: usize, level: &str, run: &GhostRun) {
+    let res = serde_json::to_string(run)
+        .map_err(Error::from)
+        .and_then(|data| write_storage(&ghost_key(slot, level), &data));
+    if let Err(err) = res {
+        log::error!("Can't write ghost of {level} err {err:?}");
+    }
+}
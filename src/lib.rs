@@ -1,10 +1,47 @@
+pub mod air;
+pub mod audio;
+pub mod bindings;
+pub mod checkpoint;
+pub mod death;
+pub mod events;
+pub mod fan;
+pub mod fields;
+pub mod ghost;
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+pub mod platform;
+pub mod replay;
+pub mod save;
+pub mod sim;
+pub mod splits;
+pub mod touch;
+pub mod tuning;
 #[cfg(target_arch = "wasm32")]
 pub mod web;
+#[cfg(not(target_arch = "wasm32"))]
+use native::*;
 #[cfg(target_arch = "wasm32")]
 use web::*;
 
-use std::{cell::RefCell, collections::HashMap, io::Cursor, time::Duration};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io::Cursor,
+    time::Duration,
+};
 
+use air::{AirTank, Pump, Refill};
+use audio::{
+    impact_rate, impact_volume, level_music, parse_sound_banks, stereo_panning, AudioSettings, Bus,
+    SoundBank, IMPACT_MIN_SPEED,
+};
+use bindings::{bindable_keys, capture_keys, key_name, raw_key, Bindings, Rebind};
+use checkpoint::{Respawn, RespawnPolicy};
+use death::{out_of_bounds, DeathCause, DeathState, DeathTally};
+use events::{EventBus, EventListener, GameEvent, Impact};
+use fan::Wind;
+use ghost::{load_ghost, save_ghost, GhostRun};
 use glam::{IVec2, UVec2};
 use kira::{
     manager::{AudioManager, AudioManagerSettings, DefaultBackend},
@@ -12,28 +49,41 @@ use kira::{
         static_sound::{StaticSoundData, StaticSoundHandle},
         PlaybackState,
     },
+    track::{TrackBuilder, TrackHandle},
     tween::Tween,
 };
-use rand::{seq::SliceRandom, thread_rng, Rng};
-use roast_2d::{handle::Handle, ldtk::LdtkProject, prelude::*};
-
-const ACCEL_DEFLATION: f32 = 900.0;
-const ACCEL_GROUND: f32 = 600.0;
-const ACCEL_AIR: f32 = 300.0;
-const PLAYER_JUMP_VEL: f32 = 200.0;
-const FRICTION_GROUND: f32 = 2.;
-const FRICTION_AIR: f32 = 2.;
-const JUMP_HIGH_TIME: f32 = 0.08;
-const JUMP_HIGH_ACCEL: f32 = 780.0;
-const INFLATION_SPEED: f32 = 1.2;
-const MIN_INFLATION: f32 = 1.6;
-const MAX_INFLATION: f32 = 8.;
+use platform::PlatformPath;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use replay::{Playback, Replay};
+use roast_2d::{
+    handle::Handle,
+    input::InputState,
+    ldtk::{LayerType, LdtkProject},
+    prelude::*,
+};
+use save::{last_slot, Progress, SaveManager, SAVE_SLOTS};
+use serde::{Deserialize, Serialize};
+use splits::{load_pb, save_pb, Splits};
+use touch::{logical_size, TouchLayout};
+use tuning::Tuning;
+
 const PLAYER_SIZE: Vec2 = Vec2::new(32.0, 32.0);
-const INFLATOR_SPEED: f32 = 0.5;
+/// IntGrid value of the collision layer for tiles that pop an inflated balloon
+pub const SHARP_TILE: u16 = 2;
+/// Overlap with a solid entity the inflation ignores, the engine leaves entities this close
+const SOLID_TOLERANCE: f32 = 0.5;
 
 const LEVEL_PATH: &str = "game.ldtk";
+const TUNING_PATH: &str = "tuning.json";
+const SOUNDS_PATH: &str = "sounds.json";
+const TUNING_RELOAD_INTERVAL: f32 = 1.0;
+/// Updates a read of the tuning file may stay pending, the engine fetches assets every frame
+const TUNING_READ_FRAMES: u32 = 2;
 const VIEW_SIZE: Vec2 = Vec2::new(512.0, 512.0);
 const WINDOW_SIZE: UVec2 = UVec2::new(512, 512);
+const HIGHLIGHT: Color = Color::rgb(0x42, 0xbf, 0xe8);
+const MUSIC_FADE: f32 = 1.5;
+const MUSIC_DUCKING: f32 = 0.4;
 
 thread_local! {
     static G: RefCell<Game> = RefCell::new(Game::default());
@@ -41,6 +91,13 @@ thread_local! {
     static PROJ: RefCell<LdtkProject> = RefCell::new(Default::default());
     static TEXTURE: RefCell<HashMap<String, Handle>> = RefCell::new(Default::default());
     static FONT: RefCell<FontManager> = RefCell::new(Default::default());
+    static TUNING: RefCell<TuningManager> = RefCell::new(Default::default());
+    static SAVE: RefCell<SaveManager> = RefCell::new(Default::default());
+    static BINDINGS: RefCell<Bindings> = RefCell::new(Bindings::load());
+    static TOUCH: RefCell<TouchOverlay> = RefCell::new(Default::default());
+    static EVENTS: RefCell<EventBus> = RefCell::new(Default::default());
+    /// Randomness of the game, reseeded for every attempt so replays sound the same
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
 }
 
 fn load_texture(eng: &mut Engine, path: &str) -> Handle {
@@ -55,28 +112,181 @@ fn load_texture(eng: &mut Engine, path: &str) -> Handle {
     })
 }
 
-fn lerp_size(ori_size: Vec2, inflation_rate: f32) -> Vec2 {
-    (ori_size * MAX_INFLATION) * ((inflation_rate) / MAX_INFLATION).powi(2)
+fn level_identifier(level: usize) -> String {
+    format!("Level_{}", level)
+}
+
+/// Levels of the campaign, `Level_0`, `Level_1`... until the first missing one
+fn campaign_levels(proj: &LdtkProject) -> Vec<String> {
+    (0..)
+        .map(level_identifier)
+        .take_while(|identifier| proj.get_level(identifier).is_ok())
+        .collect()
+}
+
+fn project_levels(proj: &LdtkProject) -> Vec<String> {
+    let mut levels = campaign_levels(proj);
+    for level in &proj.levels {
+        if !levels.contains(&level.identifier) {
+            levels.push(level.identifier.clone());
+        }
+    }
+    levels
+}
+
+fn next_level(proj: &LdtkProject, level: &str) -> Option<String> {
+    let levels = campaign_levels(proj);
+    let index = levels.iter().position(|l| l == level)?;
+    levels.get(index + 1).cloned()
+}
+
+fn is_unlocked(progress: &Progress, level: &str) -> bool {
+    level == level_identifier(0) || progress.unlocked.contains(level)
+}
+
+fn find_entity(proj: &LdtkProject, iid: &str) -> Option<(String, Vec2)> {
+    proj.levels.iter().find_map(|level| {
+        level
+            .layer_instances
+            .iter()
+            .flat_map(|layer| &layer.entity_instances)
+            .find(|ent| ent.iid == iid)
+            .map(|ent| {
+                let pos = Vec2::new(
+                    (ent.px.0 + ent.width / 2) as f32,
+                    (ent.px.1 + ent.height / 2) as f32,
+                );
+                (level.identifier.clone(), pos)
+            })
+    })
+}
+
+fn text_sprite(eng: &mut Engine, font: Font, content: String, scale: f32, color: Color) -> Sprite {
+    let text = Text::new(content, font, scale, color);
+    let (texture, size) = eng.create_text_texture(text);
+    Sprite::new(texture, size)
+}
+
+fn format_time(secs: f32) -> String {
+    let minutes = (secs / 60.0) as usize;
+    let secs = secs - minutes as f32 * 60.0;
+    format!("{minutes}:{secs:05.2}")
+}
+
+fn bind_keys(input: &mut InputState) {
+    BINDINGS.with_borrow(|bindings| bindings.apply(input));
+}
+
+fn emit(event: GameEvent) {
+    EVENTS.with_borrow_mut(|events| events.emit(event));
+}
+
+/// Size of the screen in view coordinates, the view size until the window is known
+fn screen_size(eng: &Engine) -> Vec2 {
+    window_size().map_or(eng.view_size(), |window| {
+        logical_size(window, eng.view_size())
+    })
+}
+
+fn update_touch(eng: &mut Engine) {
+    TOUCH.with_borrow_mut(|touch| touch.update(eng));
+}
+
+fn read_controls(eng: &Engine) -> Controls {
+    let touch = TOUCH.with_borrow(|touch| touch.controls);
+    Controls::from_input(eng.input()).merge(&touch)
+}
+
+fn load_level(eng: &mut Engine, level: &str) -> Result<()> {
+    PROJ.with_borrow(|proj| {
+        eng.load_level(proj, level)?;
+        // entities are spawned in the order of the LDtk layers
+        let level_entities = proj
+            .get_level(level)?
+            .layer_instances
+            .iter()
+            .filter(|layer| layer.r#type == LayerType::Entities)
+            .flat_map(|layer| &layer.entity_instances)
+            .map(|ent| ent.iid.clone())
+            .zip(eng.world().entities().map(|ent| ent.borrow().ent_ref))
+            .collect();
+        G.with_borrow_mut(|g| {
+            g.level_entities = level_entities;
+            g.winds.clear();
+        });
+        Ok(())
+    })
+}
+
+fn lerp_size(ori_size: Vec2, inflation_rate: f32, max_inflation: f32) -> Vec2 {
+    (ori_size * max_inflation) * ((inflation_rate) / max_inflation).powi(2)
 }
 
 pub struct Game {
     pub dead: usize,
-    pub current_level: usize,
-    pub remained_air: f32,
-    pub loading_level: Option<usize>,
+    pub current_level: String,
+    pub air: AirTank,
+    pub loading_level: Option<String>,
+    /// Where the player spawns in the current level, the level's Player entity if `None`
+    pub spawn_point: Option<Vec2>,
+    pub completed: bool,
+    pub level_entities: HashMap<String, EntityRef>,
+    pub controls: Controls,
+    /// Watching a replay, progress is not recorded
+    pub replaying: bool,
+    pub player: Option<Body>,
+    pub ghost: Option<GhostRun>,
+    pub tuning: Tuning,
+    pub winds: Vec<Wind>,
+    pub death: DeathState,
+    pub deaths: DeathTally,
+    pub respawn: Option<Respawn>,
+    /// Air capacity raised by each upgrading `Inflator`, they are kept for good
+    pub upgrades: BTreeMap<String, f32>,
+    /// LDtk iids of the entities consumed in the current level that stay consumed at checkpoints
+    pub removed: Vec<String>,
 }
 
 impl Default for Game {
     fn default() -> Self {
         Self {
             dead: 0,
-            current_level: 0,
-            remained_air: 0.0,
+            current_level: level_identifier(0),
+            air: AirTank::empty(1.0),
             loading_level: None,
+            spawn_point: None,
+            completed: false,
+            level_entities: HashMap::new(),
+            controls: Controls::default(),
+            replaying: false,
+            player: None,
+            ghost: None,
+            tuning: Tuning::default(),
+            winds: Vec::new(),
+            death: DeathState::Alive,
+            deaths: DeathTally::default(),
+            respawn: None,
+            upgrades: BTreeMap::new(),
+            removed: Vec::new(),
         }
     }
 }
 
+impl Game {
+    pub fn start_level(&mut self, level: String) {
+        self.current_level = level;
+        self.loading_level = None;
+        self.spawn_point = None;
+        self.completed = false;
+        self.respawn = None;
+    }
+
+    pub fn air_capacity(&self) -> f32 {
+        self.tuning.air_capacity + self.upgrades.values().sum::<f32>()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Action {
     Left = 1,
@@ -87,6 +297,45 @@ pub enum Action {
     Inflate,
     Deflate,
     Restart,
+    Confirm,
+    ToggleGhost,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::Left,
+        Action::Right,
+        Action::Up,
+        Action::Down,
+        Action::Jump,
+        Action::Inflate,
+        Action::Deflate,
+        Action::Restart,
+        Action::Confirm,
+        Action::ToggleGhost,
+        Action::Pause,
+    ];
+
+    fn bit(self) -> u16 {
+        1 << (self as u8)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Jump => "Jump",
+            Action::Inflate => "Inflate",
+            Action::Deflate => "Deflate",
+            Action::Restart => "Restart",
+            Action::Confirm => "Confirm",
+            Action::ToggleGhost => "Ghost",
+            Action::Pause => "Pause",
+        }
+    }
 }
 
 impl From<Action> for ActionId {
@@ -95,12 +344,222 @@ impl From<Action> for ActionId {
     }
 }
 
+pub const ANALOG_ACTIONS: [Action; 6] = [
+    Action::Left,
+    Action::Right,
+    Action::Up,
+    Action::Down,
+    Action::Inflate,
+    Action::Deflate,
+];
+
+/// Analog values up to this are the drift of a resting stick or trigger
+pub const ANALOG_DEADZONE: f32 = 0.2;
+
+/// Strength of an analog value, rescaled from past the deadzone to 0..1
+pub fn analog_strength(value: f32) -> f32 {
+    ((value - ANALOG_DEADZONE) / (1.0 - ANALOG_DEADZONE)).clamp(0.0, 1.0)
+}
+
+fn quantize(strength: f32) -> u8 {
+    (strength.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Controls {
+    pressed: u16,
+    just_pressed: u16,
+    /// Strength of the pressed `ANALOG_ACTIONS` out of 255, quantized so replays stay exact
+    strength: [u8; 6],
+}
+
+impl Controls {
+    pub fn from_input(input: &InputState) -> Self {
+        let mut controls = Self::default();
+        for action in Action::ALL {
+            if !input.pressed(action) {
+                continue;
+            }
+            if let Some(i) = ANALOG_ACTIONS.iter().position(|&a| a == action) {
+                let state = input.get_state(action).map_or(1.0, |state| state.0);
+                controls.strength[i] = quantize(analog_strength(state));
+                if controls.strength[i] == 0 {
+                    continue;
+                }
+            }
+            controls.pressed |= action.bit();
+            if input.just_pressed(action) {
+                controls.just_pressed |= action.bit();
+            }
+        }
+        controls
+    }
+
+    /// Hold `actions`, use `after` to derive which of them are just pressed
+    pub fn holding(actions: &[Action]) -> Self {
+        let pressed = actions.iter().fold(0, |bits, action| bits | action.bit());
+        Self::from_bits(pressed)
+    }
+
+    pub fn with_strength(mut self, action: Action, strength: f32) -> Self {
+        if let Some(i) = ANALOG_ACTIONS.iter().position(|&a| a == action) {
+            self.pressed |= action.bit();
+            self.strength[i] = quantize(strength);
+        }
+        self
+    }
+
+    pub fn merge(self, other: &Controls) -> Self {
+        let mut strength = self.strength;
+        for (s, other) in strength.iter_mut().zip(other.strength) {
+            *s = (*s).max(other);
+        }
+        Self {
+            pressed: self.pressed | other.pressed,
+            just_pressed: self.just_pressed | other.just_pressed,
+            strength,
+        }
+    }
+
+    pub fn after(self, previous: &Controls) -> Self {
+        Self {
+            just_pressed: self.pressed & !previous.pressed,
+            ..self
+        }
+    }
+
+    pub(crate) fn from_bits(pressed: u16) -> Self {
+        let mut strength = [0; 6];
+        for (i, action) in ANALOG_ACTIONS.into_iter().enumerate() {
+            if pressed & action.bit() != 0 {
+                strength[i] = u8::MAX;
+            }
+        }
+        Self {
+            pressed,
+            just_pressed: 0,
+            strength,
+        }
+    }
+
+    pub(crate) fn from_parts(pressed: u16, strength: Option<[u8; 6]>) -> Self {
+        let controls = Self::from_bits(pressed);
+        match strength {
+            Some(strength) => Self {
+                strength,
+                ..controls
+            },
+            None => controls,
+        }
+    }
+
+    pub(crate) fn pressed_bits(&self) -> u16 {
+        self.pressed
+    }
+
+    /// Strength of the analog actions, `None` when every pressed action is fully pressed
+    pub(crate) fn strength_bytes(&self) -> Option<[u8; 6]> {
+        (self.strength != Self::from_bits(self.pressed).strength).then_some(self.strength)
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed & action.bit() != 0
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed & action.bit() != 0
+    }
+
+    pub fn strength(&self, action: Action) -> f32 {
+        if !self.pressed(action) {
+            return 0.0;
+        }
+        match ANALOG_ACTIONS.iter().position(|&a| a == action) {
+            Some(i) => self.strength[i] as f32 / 255.0,
+            None => 1.0,
+        }
+    }
+}
+
+/// Message sent by switches to their targets, the payload is the `bool` active state
+const MSG_ACTIVATE: u32 = 1;
+/// Remove an entity consumed before the checkpoint the player respawns at
+const MSG_REMOVE: u32 = 2;
+/// Like `MSG_ACTIVATE` without feedback, restores the targets of a removed button
+const MSG_RESTORE: u32 = 3;
+
+fn entity_iid(ent_ref: EntityRef) -> Option<String> {
+    G.with_borrow(|g| {
+        g.level_entities
+            .iter()
+            .find(|(_, r)| **r == ent_ref)
+            .map(|(iid, _)| iid.clone())
+    })
+}
+
+fn consume(ent: &Entity, policy: RespawnPolicy) {
+    if policy != RespawnPolicy::Keep {
+        return;
+    }
+    if let Some(iid) = entity_iid(ent.ent_ref) {
+        G.with_borrow_mut(|g| g.removed.push(iid));
+    }
+}
+
+pub trait Activatable {
+    fn set_active(&mut self, eng: &mut Engine, ent: &mut Entity, active: bool);
+
+    /// Emitted when a switch changes the state, not when it is restored
+    fn event(&self, _active: bool) -> Option<GameEvent> {
+        None
+    }
+}
+
+fn handle_activate<T: Activatable>(
+    target: &mut T,
+    eng: &mut Engine,
+    ent: &mut Entity,
+    message: u32,
+    data: Box<dyn Any>,
+) {
+    if message != MSG_ACTIVATE && message != MSG_RESTORE {
+        return;
+    }
+    let Ok(active) = data.downcast::<bool>() else {
+        log::error!("Invalid activate message for {:?}", ent.ent_ref);
+        return;
+    };
+    target.set_active(eng, ent, *active);
+    if message == MSG_ACTIVATE {
+        if let Some(event) = target.event(*active) {
+            emit(event);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Spikes {
     size: Vec2,
     anim: Animation,
 }
 
+impl Activatable for Spikes {
+    /// Active spikes are retracted and harmless
+    fn set_active(&mut self, _eng: &mut Engine, ent: &mut Entity, active: bool) {
+        if active {
+            ent.check_against = EntityGroup::NONE;
+            ent.anim = None;
+        } else {
+            ent.check_against = EntityGroup::PLAYER;
+            ent.anim = Some(self.anim.clone());
+        }
+    }
+
+    fn event(&self, active: bool) -> Option<GameEvent> {
+        active.then_some(GameEvent::SpikesRemoved)
+    }
+}
+
 impl EntityType for Spikes {
     fn load(eng: &mut Engine) -> Self {
         let size = Vec2::new(32., 10.);
@@ -119,6 +578,9 @@ impl EntityType for Spikes {
     fn touch(&mut self, eng: &mut Engine, _ent: &mut Entity, other: &mut Entity) {
         eng.kill(other.ent_ref);
     }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, data: Box<dyn Any>) {
+        handle_activate(self, eng, ent, message, data);
+    }
 }
 
 #[derive(Clone)]
@@ -143,12 +605,70 @@ impl EntityType for Crown {
         ent.physics = EntityPhysics::PASSIVE;
         ent.gravity = 0.;
     }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, _data: Box<dyn Any>) {
+        if message == MSG_REMOVE {
+            eng.kill(ent.ent_ref);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ButtonMode {
+    /// Activate targets for good and disappear
+    Once,
+    /// Each press flips the targets
+    Toggle,
+    /// Targets are active while the button is held
+    Momentary,
+    /// A press activates targets for `duration` seconds
+    Timed,
 }
 
 #[derive(Clone)]
 pub struct Button {
     size: Vec2,
     anim: Animation,
+    mode: ButtonMode,
+    duration: f32,
+    /// LDtk iids of the driven entities, every `Spikes` of the level if empty
+    targets: Vec<String>,
+    policy: RespawnPolicy,
+    active: bool,
+    pressed: bool,
+    touching: bool,
+    timer: f32,
+}
+
+impl Button {
+    fn activate(&mut self, eng: &mut Engine, ent: &mut Entity, active: bool) {
+        self.drive(eng, ent, MSG_ACTIVATE, active);
+    }
+
+    fn drive(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, active: bool) {
+        let targets: Vec<EntityRef> = if self.targets.is_empty() {
+            eng.world()
+                .entities()
+                .filter_map(|ent| {
+                    let ent = ent.try_borrow().ok()?;
+                    ent.ent_type.is::<Spikes>().then_some(ent.ent_ref)
+                })
+                .collect()
+        } else {
+            G.with_borrow(|g| {
+                self.targets
+                    .iter()
+                    .filter_map(|iid| g.level_entities.get(iid).copied())
+                    .collect()
+            })
+        };
+        for target in targets {
+            eng.message(target, message, Box::new(active));
+        }
+        self.active = active;
+        if let Some(anim) = ent.anim.as_mut() {
+            anim.sheet.color = if active { GRAY } else { WHITE };
+        }
+    }
 }
 
 impl EntityType for Button {
@@ -157,7 +677,18 @@ impl EntityType for Button {
         let texture = load_texture(eng, "hammer.png");
         let sheet = Sprite::with_sizef(texture, size);
         let anim = Animation::new(sheet);
-        Self { size, anim }
+        Self {
+            size,
+            anim,
+            mode: ButtonMode::Once,
+            duration: 3.0,
+            targets: Vec::new(),
+            policy: RespawnPolicy::Keep,
+            active: false,
+            pressed: false,
+            touching: false,
+            timer: 0.0,
+        }
     }
     fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
         ent.size = self.size;
@@ -166,21 +697,81 @@ impl EntityType for Button {
         ent.physics = EntityPhysics::FIXED;
         ent.gravity = 0.;
     }
-    fn touch(&mut self, eng: &mut Engine, ent: &mut Entity, _other: &mut Entity) {
-        let mut spikes = Vec::new();
-        for ent in eng.world().entities() {
-            let Ok(ent) = ent.try_borrow() else {
-                continue;
-            };
-            if ent.ent_type.is::<Spikes>() {
-                spikes.push(ent.ent_ref);
-            }
+    fn settings(&mut self, _eng: &mut Engine, _ent: &mut Entity, settings: serde_json::Value) {
+        self.mode = fields::variant(
+            &settings,
+            "Mode",
+            &[
+                ("Once", ButtonMode::Once),
+                ("Toggle", ButtonMode::Toggle),
+                ("Momentary", ButtonMode::Momentary),
+                ("Timed", ButtonMode::Timed),
+            ],
+            ButtonMode::Once,
+        );
+        if let Some(duration) = fields::float(&settings, "Duration") {
+            self.duration = duration;
+        }
+        self.policy = RespawnPolicy::from_settings(&settings);
+        if let Some(targets) = settings.get("Targets").and_then(|v| v.as_array()) {
+            self.targets = targets
+                .iter()
+                .filter_map(|target| target.get("entityIid")?.as_str())
+                .map(ToString::to_string)
+                .collect();
+        }
+    }
+    fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        // touch is called after update, so react to the touches of the last frame
+        let touching = std::mem::take(&mut self.touching);
+        let just_pressed = touching && !self.pressed;
+        self.pressed = touching;
+        if just_pressed {
+            emit(GameEvent::ButtonPressed);
         }
 
-        for ent_ref in spikes {
-            eng.kill(ent_ref);
+        match self.mode {
+            ButtonMode::Once => {
+                if just_pressed {
+                    self.activate(eng, ent, true);
+                    eng.kill(ent.ent_ref);
+                    consume(ent, self.policy);
+                }
+            }
+            ButtonMode::Toggle => {
+                if just_pressed {
+                    self.activate(eng, ent, !self.active);
+                }
+            }
+            ButtonMode::Momentary => {
+                if touching != self.active {
+                    self.activate(eng, ent, touching);
+                }
+            }
+            ButtonMode::Timed => {
+                if touching {
+                    self.timer = self.duration;
+                    if !self.active {
+                        self.activate(eng, ent, true);
+                    }
+                } else if self.active {
+                    self.timer -= eng.tick;
+                    if self.timer <= 0.0 {
+                        self.activate(eng, ent, false);
+                    }
+                }
+            }
+        }
+    }
+    fn touch(&mut self, _eng: &mut Engine, _ent: &mut Entity, _other: &mut Entity) {
+        self.touching = true;
+    }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, _data: Box<dyn Any>) {
+        // the targets stay active like when the button was pressed
+        if message == MSG_REMOVE && self.mode == ButtonMode::Once {
+            self.drive(eng, ent, MSG_RESTORE, true);
+            eng.kill(ent.ent_ref);
         }
-        eng.kill(ent.ent_ref);
     }
 }
 
@@ -188,6 +779,8 @@ impl EntityType for Button {
 pub struct Inflator {
     size: Vec2,
     anim: Animation,
+    pump: Pump,
+    policy: RespawnPolicy,
 }
 
 impl EntityType for Inflator {
@@ -196,7 +789,12 @@ impl EntityType for Inflator {
         let texture = load_texture(eng, "air-pump.png");
         let sheet = Sprite::with_sizef(texture, size);
         let anim = Animation::new(sheet);
-        Self { size, anim }
+        Self {
+            size,
+            anim,
+            pump: Pump::new(Refill::default()),
+            policy: RespawnPolicy::Keep,
+        }
     }
 
     fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
@@ -207,150 +805,538 @@ impl EntityType for Inflator {
         ent.physics = EntityPhysics::PASSIVE;
         ent.gravity = 0.;
     }
+    fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        if self.pump.update(eng.tick) {
+            if let Some(anim) = ent.anim.as_mut() {
+                anim.sheet.color = WHITE;
+            }
+        }
+    }
     fn touch(&mut self, eng: &mut Engine, ent: &mut Entity, _other: &mut Entity) {
-        // reset inflator
-        G.with_borrow_mut(|g| {
-            g.remained_air = 1.0;
+        let iid = entity_iid(ent.ent_ref);
+        let used = G.with_borrow_mut(|g| {
+            let upgraded = iid.as_ref().is_some_and(|iid| g.upgrades.contains_key(iid));
+            let raise = self.pump.use_on(&mut g.air, upgraded)?;
+            Some((raise, g.replaying))
         });
-        eng.kill(ent.ent_ref);
+        let Some((raise, replaying)) = used else {
+            return;
+        };
+        if let Some(iid) = iid.filter(|_| raise > 0.0) {
+            G.with_borrow_mut(|g| g.upgrades.insert(iid.clone(), raise));
+            if !replaying {
+                SAVE.with_borrow_mut(|save| save.record_upgrade(&iid, raise));
+            }
+        }
+        emit(GameEvent::ItemPickedUp);
+        if self.pump.reusable() {
+            if let Some(anim) = ent.anim.as_mut() {
+                anim.sheet.color = GRAY;
+            }
+        } else {
+            eng.kill(ent.ent_ref);
+            consume(ent, self.policy);
+        }
+    }
+    fn settings(&mut self, _eng: &mut Engine, _ent: &mut Entity, settings: serde_json::Value) {
+        self.pump = Pump::new(Refill::from_settings(&settings));
+        self.policy = RespawnPolicy::from_settings(&settings);
+    }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, _data: Box<dyn Any>) {
+        if message == MSG_REMOVE {
+            eng.kill(ent.ent_ref);
+        }
     }
 }
 
 #[derive(Clone)]
-pub struct Door {
+pub struct Fan {
     size: Vec2,
     anim: Animation,
+    /// Index of its wind in `Game::winds`
+    wind: Option<usize>,
+    /// Whether it blows until a switch flips it
+    blowing: bool,
 }
 
-impl EntityType for Door {
+impl Activatable for Fan {
+    fn set_active(&mut self, _eng: &mut Engine, ent: &mut Entity, active: bool) {
+        let blowing = self.blowing != active;
+        if let Some(index) = self.wind {
+            G.with_borrow_mut(|g| g.winds[index].blowing = blowing);
+        }
+        if let Some(anim) = ent.anim.as_mut() {
+            anim.sheet.color = if blowing { WHITE } else { GRAY };
+        }
+    }
+}
+
+impl EntityType for Fan {
     fn load(eng: &mut Engine) -> Self {
         let size = Vec2::new(32., 32.);
-        let texture = load_texture(eng, "exit.png");
+        let texture = load_texture(eng, "fan.png");
         let sheet = Sprite::with_sizef(texture, size);
         let anim = Animation::new(sheet);
-        Self { size, anim }
+        Self {
+            size,
+            anim,
+            wind: None,
+            blowing: true,
+        }
     }
     fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
         ent.size = self.size;
         ent.anim = Some(self.anim.clone());
-        ent.group = EntityGroup::PICKUP;
-        ent.check_against = EntityGroup::PLAYER;
-        ent.physics = EntityPhysics::FIXED;
+        ent.physics = EntityPhysics::NONE;
         ent.gravity = 0.;
     }
-    fn touch(&mut self, _eng: &mut Engine, _ent: &mut Entity, _other: &mut Entity) {
+    fn settings(&mut self, _eng: &mut Engine, ent: &mut Entity, settings: serde_json::Value) {
+        let wind = Wind::from_settings(ent.pos, &settings);
+        self.blowing = wind.blowing;
+        if let Some(anim) = ent.anim.as_mut() {
+            anim.sheet.color = if self.blowing { WHITE } else { GRAY };
+        }
         G.with_borrow_mut(|g| {
-            g.loading_level = Some(g.current_level + 1);
+            self.wind = Some(g.winds.len());
+            g.winds.push(wind);
         });
     }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, data: Box<dyn Any>) {
+        handle_activate(self, eng, ent, message, data);
+    }
 }
 
+/// Solid platform following a path authored in LDtk, the engine carries what stands on it
 #[derive(Clone)]
-pub struct Player {
-    can_jump: bool,
-    high_jump_time: f32,
-    inflation_rate: f32,
-    original_size: Vec2,
-    normal: Vec2,
-    inflation: f32,
-    anim: Animation,
+pub struct MovingPlatform {
     size: Vec2,
+    anim: Animation,
+    path: Option<PlatformPath>,
+    /// Whether it runs until a switch flips it
+    running: bool,
+    /// Switches stop the platform, or run it if it starts stopped
+    active: bool,
 }
 
-impl EntityType for Player {
+impl Activatable for MovingPlatform {
+    fn set_active(&mut self, _eng: &mut Engine, ent: &mut Entity, active: bool) {
+        self.active = active;
+        if self.running == active {
+            ent.vel = Vec2::ZERO;
+        }
+    }
+}
+
+impl EntityType for MovingPlatform {
     fn load(eng: &mut Engine) -> Self {
-        let original_size = PLAYER_SIZE;
-        let inflation_rate = 2.8;
-        let normal = Vec2::new(1.0, 0.0);
-        let size = lerp_size(PLAYER_SIZE, inflation_rate).min(PLAYER_SIZE);
-        let texture = load_texture(eng, "ball.png");
+        let size = Vec2::new(64., 16.);
+        let texture = load_texture(eng, "platform.png");
         let sheet = Sprite::with_sizef(texture, size);
         let anim = Animation::new(sheet);
-
         Self {
-            can_jump: false,
-            high_jump_time: 0.0,
-            inflation_rate,
-            original_size,
-            normal,
-            inflation: 0.0,
-            anim,
             size,
+            anim,
+            path: None,
+            running: true,
+            active: false,
         }
     }
-    fn init(&mut self, eng: &mut Engine, ent: &mut Entity) {
-        ent.check_against = EntityGroup::ITEM;
-        ent.physics = EntityPhysics::ACTIVE;
-        ent.group = EntityGroup::PLAYER;
-        ent.gravity = 1.0;
-        ent.mass = 1.0;
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
         ent.size = self.size;
         ent.anim = Some(self.anim.clone());
-
-        // init items
-        G.with_borrow_mut(|g| {
-            g.remained_air = 0.0;
-        });
-
-        // set camera
-        let cam = eng.camera_mut();
-        cam.follow(ent.ent_ref, true);
-        cam.speed = 3.;
-        cam.min_vel = Vec2::splat(5.);
+        ent.physics = EntityPhysics::FIXED;
+        ent.gravity = 0.;
+    }
+    fn settings(&mut self, eng: &mut Engine, ent: &mut Entity, settings: serde_json::Value) {
+        let grid = eng.collision_map.as_ref().map_or(32.0, |map| map.tile_size);
+        self.path = Some(PlatformPath::from_settings(ent.pos, &settings, grid));
+        self.running = fields::boolean(&settings, "Running").unwrap_or(true);
     }
-
     fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
-        let input = eng.input();
-
-        if input.just_pressed(Action::Restart) {
-            eng.kill(ent.ent_ref);
+        if self.running == self.active {
             return;
         }
+        if let Some(path) = self.path.as_mut() {
+            ent.vel = path.velocity(ent.pos, eng.tick);
+        }
+    }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, data: Box<dyn Any>) {
+        handle_activate(self, eng, ent, message, data);
+    }
+}
+
+#[derive(Clone)]
+pub struct Gate {
+    size: Vec2,
+    anim: Animation,
+    /// Whether it starts open and switches close it
+    open: bool,
+}
+
+impl Gate {
+    fn set_open(&self, ent: &mut Entity, open: bool) {
+        if open {
+            ent.physics = EntityPhysics::NONE;
+            ent.anim = None;
+        } else {
+            ent.physics = EntityPhysics::FIXED;
+            ent.anim = Some(self.anim.clone());
+        }
+    }
+}
+
+impl Activatable for Gate {
+    fn set_active(&mut self, _eng: &mut Engine, ent: &mut Entity, active: bool) {
+        self.set_open(ent, self.open != active);
+    }
+}
+
+impl EntityType for Gate {
+    fn load(eng: &mut Engine) -> Self {
+        let size = Vec2::new(32., 64.);
+        let texture = load_texture(eng, "gate.png");
+        let sheet = Sprite::with_sizef(texture, size);
+        let anim = Animation::new(sheet);
+        Self {
+            size,
+            anim,
+            open: false,
+        }
+    }
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        ent.size = self.size;
+        ent.gravity = 0.;
+        self.set_open(ent, false);
+    }
+    fn settings(&mut self, _eng: &mut Engine, ent: &mut Entity, settings: serde_json::Value) {
+        self.open = fields::boolean(&settings, "Open").unwrap_or(false);
+        self.set_open(ent, self.open);
+    }
+    fn message(&mut self, eng: &mut Engine, ent: &mut Entity, message: u32, data: Box<dyn Any>) {
+        handle_activate(self, eng, ent, message, data);
+    }
+}
+
+#[derive(Clone)]
+pub struct Checkpoint {
+    size: Vec2,
+    anim: Animation,
+    iid: Option<String>,
+}
+
+impl EntityType for Checkpoint {
+    fn load(eng: &mut Engine) -> Self {
+        let size = Vec2::new(32., 32.);
+        let texture = load_texture(eng, "checkpoint.png");
+        let sheet = Sprite::with_sizef(texture, size);
+        let anim = Animation::new(sheet);
+        Self {
+            size,
+            anim,
+            iid: None,
+        }
+    }
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        ent.size = self.size;
+        ent.anim = Some(self.anim.clone());
+        ent.group = EntityGroup::ITEM;
+        ent.gravity = 0.;
+    }
+    fn update(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        if self.iid.is_none() {
+            self.iid = entity_iid(ent.ent_ref);
+        }
+        let reached = G.with_borrow(|g| {
+            g.respawn
+                .as_ref()
+                .is_some_and(|respawn| Some(&respawn.checkpoint) == self.iid.as_ref())
+        });
+        if let Some(anim) = ent.anim.as_mut() {
+            anim.sheet.color = if reached { WHITE } else { GRAY };
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Door {
+    size: Vec2,
+    anim: Animation,
+    level: Option<String>,
+    spawn: Option<Vec2>,
+}
+
+impl EntityType for Door {
+    fn load(eng: &mut Engine) -> Self {
+        let size = Vec2::new(32., 32.);
+        let texture = load_texture(eng, "exit.png");
+        let sheet = Sprite::with_sizef(texture, size);
+        let anim = Animation::new(sheet);
+        Self {
+            size,
+            anim,
+            level: None,
+            spawn: None,
+        }
+    }
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        ent.size = self.size;
+        ent.anim = Some(self.anim.clone());
+        ent.group = EntityGroup::PICKUP;
+        ent.check_against = EntityGroup::PLAYER;
+        ent.physics = EntityPhysics::FIXED;
+        ent.gravity = 0.;
+    }
+    fn settings(&mut self, _eng: &mut Engine, _ent: &mut Entity, settings: serde_json::Value) {
+        if let Some(level) = settings.get("Level").and_then(|v| v.as_str()) {
+            // a misspelled level falls back to the next one of the campaign
+            if PROJ.with_borrow(|proj| proj.get_level(level).is_ok()) {
+                self.level = Some(level.to_string());
+            } else {
+                log::error!("Can't find door level {level}");
+            }
+        }
+        // Spawn is an entity reference, the player enters its level at its position
+        if let Some(iid) = settings
+            .get("Spawn")
+            .and_then(|v| v.get("entityIid"))
+            .and_then(|v| v.as_str())
+        {
+            match PROJ.with_borrow(|proj| find_entity(proj, iid)) {
+                Some((level, pos)) => {
+                    self.level = Some(level);
+                    self.spawn = Some(pos);
+                }
+                None => log::error!("Can't find door spawn {iid}"),
+            }
+        }
+    }
+    fn touch(&mut self, _eng: &mut Engine, _ent: &mut Entity, _other: &mut Entity) {
+        emit(GameEvent::DoorEntered);
+        G.with_borrow_mut(|g| {
+            let level = self
+                .level
+                .clone()
+                .or_else(|| PROJ.with_borrow(|proj| next_level(proj, &g.current_level)));
+            match level {
+                Some(level) => {
+                    g.loading_level = Some(level);
+                    g.spawn_point = self.spawn;
+                }
+                None => g.completed = true,
+            }
+        });
+    }
+}
+
+/// Physics state of an entity, the part of `Entity` driven by `Balloon`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Body {
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub vel: Vec2,
+    pub accel: Vec2,
+    pub friction: Vec2,
+    pub on_ground: bool,
+    pub gravity: f32,
+    pub mass: f32,
+    pub restitution: f32,
+}
+
+impl Body {
+    pub fn from_entity(ent: &Entity) -> Self {
+        Self {
+            pos: ent.pos,
+            size: ent.size,
+            vel: ent.vel,
+            accel: ent.accel,
+            friction: ent.friction,
+            on_ground: ent.on_ground,
+            gravity: ent.gravity,
+            mass: ent.mass,
+            restitution: ent.restitution,
+        }
+    }
+
+    pub fn apply(&self, ent: &mut Entity) {
+        ent.pos = self.pos;
+        ent.size = self.size;
+        ent.vel = self.vel;
+        ent.accel = self.accel;
+        ent.friction = self.friction;
+        ent.on_ground = self.on_ground;
+        ent.gravity = self.gravity;
+        ent.mass = self.mass;
+        ent.restitution = self.restitution;
+    }
+
+    pub fn bounds(&self) -> Rect {
+        let half_size = self.size * 0.5;
+        Rect {
+            min: self.pos - half_size,
+            max: self.pos + half_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalloonSound {
+    Inflate,
+    Deflate,
+    Silence,
+}
+
+#[derive(Debug, Default)]
+pub struct BalloonStep {
+    pub restart: bool,
+    pub burst: bool,
+    pub resized: bool,
+    pub sound: Option<BalloonSound>,
+    /// Facing of the sprite, `None` if the step was cut short
+    pub flip_x: Option<bool>,
+}
+
+/// Movement of the balloon, it only touches the `Body` so it runs the same with or without an `Engine`
+#[derive(Clone)]
+pub struct Balloon {
+    can_jump: bool,
+    high_jump_time: f32,
+    inflation_rate: f32,
+    original_size: Vec2,
+    normal: Vec2,
+    inflation: f32,
+    /// Builds up while inflating against a wall, the balloon pops at 1
+    pressure: f32,
+    tuning: Tuning,
+}
+
+impl Default for Balloon {
+    fn default() -> Self {
+        Self::new(Tuning::default())
+    }
+}
+
+impl Balloon {
+    pub fn new(tuning: Tuning) -> Self {
+        Self {
+            can_jump: false,
+            high_jump_time: 0.0,
+            inflation_rate: tuning.start_inflation,
+            original_size: PLAYER_SIZE,
+            normal: Vec2::new(1.0, 0.0),
+            inflation: 0.0,
+            pressure: 0.0,
+            tuning,
+        }
+    }
+
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        self.tuning = tuning;
+    }
+
+    /// Inflation from 0 at the tuned minimum to 1 at the maximum
+    pub fn inflation_share(&self) -> f32 {
+        let t = self.tuning;
+        if t.max_inflation <= t.min_inflation {
+            return 0.0;
+        }
+        ((self.inflation_rate - t.min_inflation) / (t.max_inflation - t.min_inflation))
+            .clamp(0.0, 1.0)
+    }
+
+    pub fn inflation_rate(&self) -> f32 {
+        self.inflation_rate
+    }
+
+    pub fn set_inflation_rate(&mut self, inflation_rate: f32, body: &mut Body) {
+        let t = self.tuning;
+        self.inflation_rate = inflation_rate.clamp(t.min_inflation, t.max_inflation);
+        body.size = self.size();
+        self.apply_weight(body);
+    }
+
+    /// Bigger balloons are lighter and bouncier
+    fn apply_weight(&self, body: &mut Body) {
+        body.mass = (1.0 / self.inflation_rate).clamp(0.1, 1.0);
+        body.gravity = (1.0 / self.inflation_rate).clamp(0.3, 1.0);
+        body.restitution = (self.inflation_rate / 10.0).clamp(0.1, 2.0);
+    }
+
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    pub fn pops_on(&self, tile: u16) -> bool {
+        tile == SHARP_TILE && self.inflation_rate >= self.tuning.sharp_inflation
+    }
+
+    pub fn size(&self) -> Vec2 {
+        lerp_size(
+            self.original_size,
+            self.inflation_rate,
+            self.tuning.max_inflation,
+        )
+    }
+
+    pub fn step(
+        &mut self,
+        controls: &Controls,
+        body: &mut Body,
+        air: &mut AirTank,
+        map: Option<&CollisionMap>,
+        solids: &[Rect],
+        tick: f32,
+    ) -> BalloonStep {
+        let t = self.tuning;
+        let mut step = BalloonStep::default();
+        if controls.just_pressed(Action::Restart) {
+            step.restart = true;
+            return step;
+        }
+        air.leak(t.air_leak, tick);
 
-        ent.accel = Vec2::default();
-        ent.friction.x = if ent.on_ground {
-            FRICTION_GROUND
+        body.accel = Vec2::default();
+        body.friction.x = if body.on_ground {
+            t.friction_ground
         } else {
-            FRICTION_AIR
+            t.friction_air
         };
 
+        // a trigger inflates or deflates proportionally
         let inflation;
-        if input.pressed(Action::Inflate) && self.inflation_rate < MAX_INFLATION {
-            inflation = 1.;
-        } else if input.pressed(Action::Deflate) && self.inflation_rate > MIN_INFLATION {
-            inflation = -1.;
+        if controls.pressed(Action::Inflate) && self.inflation_rate < t.max_inflation {
+            inflation = controls.strength(Action::Inflate);
+        } else if controls.pressed(Action::Deflate) && self.inflation_rate > t.min_inflation {
+            inflation = -controls.strength(Action::Deflate);
         } else {
             inflation = 0.;
         }
 
+        // the pressure goes down unless the balloon is blocked while inflating
+        let pressure = self.pressure;
+        self.pressure = (pressure - tick / t.burst_time).max(0.0);
+
         // collision detect
         // 1. calc new pos, size...
         // 2. check collision
         // 3. cancel infliction if not possible
         if inflation != 0.0 {
             if inflation > 0.0 {
-                let remained = G.with_borrow_mut(|g| {
-                    let remained = g.remained_air > 0.0;
-                    g.remained_air = (g.remained_air - INFLATOR_SPEED * eng.tick).max(0.0);
-                    remained
-                });
-
-                if remained {
-                    S.with_borrow_mut(|sound| {
-                        sound.play_inflate(eng);
-                    });
+                if air.take(t.inflator_speed * inflation * tick) {
+                    step.sound = Some(BalloonSound::Inflate);
                 } else {
-                    return;
+                    return step;
                 }
             }
-            let inflation_rate = (self.inflation_rate + inflation * INFLATION_SPEED * eng.tick)
-                .clamp(MIN_INFLATION, MAX_INFLATION);
-            let size = lerp_size(self.original_size, inflation_rate);
-            let old_size = lerp_size(self.original_size, self.inflation_rate);
-            let pos = ent.pos + ((size - old_size).ceil() * Vec2::new(0.0, -0.5));
+            let inflation_rate = (self.inflation_rate + inflation * t.inflation_speed * tick)
+                .clamp(t.min_inflation, t.max_inflation);
+            let size = lerp_size(self.original_size, inflation_rate, t.max_inflation);
+            let old_size = self.size();
+            let pos = body.pos + ((size - old_size).ceil() * Vec2::new(0.0, -0.5));
 
             let mut collision = false;
-            if let Some(map) = eng.collision_map.as_ref() {
+            // pressing into a sharp tile pops an inflated balloon right away
+            let mut sharp = false;
+            if let Some(map) = map {
                 let tile_pos = {
                     let pos = ((pos - size * 0.5) / map.tile_size).ceil();
                     IVec2::new(pos.x as i32, pos.y as i32)
@@ -359,159 +1345,1188 @@ impl EntityType for Player {
                     let pos = ((pos + size * 0.5) / map.tile_size).floor();
                     IVec2::new(pos.x as i32, pos.y as i32)
                 };
-                'outer: for y in tile_pos.y..=corner_tile_pos.y {
+                for y in tile_pos.y..=corner_tile_pos.y {
                     for x in tile_pos.x..=corner_tile_pos.x {
-                        if !map.get(IVec2::new(x, y)).is_some_and(|v| v == 0) {
-                            collision = true;
-                            break 'outer;
+                        match map.get(IVec2::new(x, y)) {
+                            Some(0) => {}
+                            tile => {
+                                collision = true;
+                                sharp |= tile.is_some_and(|tile| self.pops_on(tile));
+                            }
                         }
                     }
                 }
             }
 
+            // solid entities block the inflation too, resting against them is fine
+            let bounds = Rect {
+                min: pos - size * 0.5 + SOLID_TOLERANCE,
+                max: pos + size * 0.5 - SOLID_TOLERANCE,
+            };
+            collision |= solids.iter().any(|solid| {
+                bounds.min.x < solid.max.x
+                    && bounds.max.x > solid.min.x
+                    && bounds.min.y < solid.max.y
+                    && bounds.max.y > solid.min.y
+            });
+
             // do inflation
             if collision {
-                return;
+                if inflation > 0.0 {
+                    self.pressure = pressure + inflation * tick / t.burst_time;
+                    step.burst = sharp || self.pressure >= 1.0;
+                }
+                return step;
             }
             self.inflation_rate = inflation_rate;
             self.inflation = inflation;
-            ent.size = size;
-            ent.pos = pos;
-            ent.mass = (1.0 / self.inflation_rate).clamp(0.1, 1.0);
-            ent.gravity = (1.0 / self.inflation_rate).clamp(0.3, 1.0);
-            ent.restitution = (self.inflation_rate / 10.0).clamp(0.1, 2.0);
-            // Scale sprite image
-            if let Some(anim) = ent.anim.as_mut() {
-                anim.sheet.size = UVec2::new(size.x as u32, size.y as u32);
-            }
+            body.size = size;
+            body.pos = pos;
+            self.apply_weight(body);
+            step.resized = true;
         } else {
             self.inflation = 0.;
-            S.with_borrow_mut(|sound| {
-                if let Some(mut s) = sound.playing.take() {
-                    s.stop(Tween {
-                        duration: Duration::from_secs_f32(0.5),
-                        ..Default::default()
-                    })
-                }
-            });
+            step.sound = Some(BalloonSound::Silence);
         }
 
+        // the stick tilt scales the walk and aims the thrust
         let mut normal = self.normal;
-        if input.pressed(Action::Right) {
-            ent.accel.x = if ent.on_ground {
-                ACCEL_GROUND
-            } else {
-                ACCEL_AIR
-            };
+        let accel = if body.on_ground {
+            t.accel_ground
+        } else {
+            t.accel_air
+        };
+        if controls.pressed(Action::Right) {
+            let strength = controls.strength(Action::Right);
+            body.accel.x = accel * strength;
             self.normal.x = 1.0;
-            normal.x = 1.0
-        } else if input.pressed(Action::Left) {
-            ent.accel.x = -if ent.on_ground {
-                ACCEL_GROUND
-            } else {
-                ACCEL_AIR
-            };
+            normal.x = strength
+        } else if controls.pressed(Action::Left) {
+            let strength = controls.strength(Action::Left);
+            body.accel.x = -accel * strength;
             self.normal.x = -1.0;
-            normal.x = -1.0
+            normal.x = -strength
         } else {
             normal.x = 0.0;
         }
 
-        if input.pressed(Action::Up) {
-            self.normal.y = -1.0;
-            normal.y = -1.0
-        } else if input.pressed(Action::Down) {
-            self.normal.y = 1.0;
-            normal.y = 1.0
+        if controls.pressed(Action::Up) {
+            normal.y = -controls.strength(Action::Up)
+        } else if controls.pressed(Action::Down) {
+            normal.y = controls.strength(Action::Down)
         } else {
-            self.normal.y = 0.0;
             normal.y = 0.0
         }
+        self.normal.y = normal.y;
 
         if normal == Vec2::ZERO {
             normal = self.normal;
         }
 
         if self.inflation < 0. {
-            ent.accel += normal * ACCEL_DEFLATION;
-
-            S.with_borrow_mut(|sound| {
-                sound.play_deflate(eng);
-            });
+            body.accel += normal * t.accel_deflation * -self.inflation;
+            step.sound = Some(BalloonSound::Deflate);
         }
 
-        if input.just_pressed(Action::Jump) {
-            if ent.on_ground && self.can_jump {
-                ent.vel.y = -PLAYER_JUMP_VEL;
+        if controls.just_pressed(Action::Jump) {
+            if body.on_ground && self.can_jump {
+                body.vel.y = -t.jump_vel;
                 self.can_jump = false;
-                self.high_jump_time = JUMP_HIGH_TIME;
+                self.high_jump_time = t.jump_high_time;
             } else if self.high_jump_time > 0. {
-                self.high_jump_time -= eng.tick;
+                self.high_jump_time -= tick;
                 let f = if self.high_jump_time < 0. {
-                    eng.tick + self.high_jump_time
+                    tick + self.high_jump_time
                 } else {
-                    eng.tick
+                    tick
                 };
-                ent.vel.y -= JUMP_HIGH_ACCEL * f;
+                body.vel.y -= t.jump_high_accel * f;
             }
         } else {
             self.high_jump_time = 0.;
-            self.can_jump = ent.on_ground;
+            self.can_jump = body.on_ground;
         }
 
-        ent.anim.as_mut().unwrap().sheet.flip_x = normal.x < 0.;
+        step.flip_x = Some(normal.x < 0.);
+        step
     }
+}
 
-    fn collide(
-        &mut self,
-        eng: &mut Engine,
-        ent: &mut Entity,
-        _normal: Vec2,
-        _trace: Option<&Trace>,
-    ) {
-        if !self.can_jump && (ent.vel.x.abs() + ent.vel.y.abs()) > 120.0 {
-            S.with_borrow_mut(|sound| {
-                sound.play_collide(eng);
-            });
-        }
-    }
+#[derive(Clone)]
+pub struct Ghost {
+    anim: Animation,
+    timer: f32,
+}
 
-    fn touch(&mut self, eng: &mut Engine, ent: &mut Entity, other: &mut Entity) {
-        if other.ent_type.is::<Crown>() {
-            eng.kill(other.ent_ref);
+impl EntityType for Ghost {
+    fn load(eng: &mut Engine) -> Self {
+        let texture = load_texture(eng, "ball.png");
+        let mut sheet = Sprite::with_sizef(texture, PLAYER_SIZE);
+        sheet.color = Color::rgba(0xb0, 0xd8, 0xff, 0x80);
+        let anim = Animation::new(sheet);
+        Self { anim, timer: 0.0 }
+    }
 
-            self.original_size *= 2.0;
-            let size = lerp_size(self.original_size, self.inflation_rate).min(self.original_size);
-            ent.size = size;
-            let texture = load_texture(eng, "ball-king.png");
-            let sheet = Sprite::with_sizef(texture, size);
-            ent.anim = Some(Animation::new(sheet));
-        }
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        ent.physics = EntityPhysics::NONE;
+        ent.group = EntityGroup::NONE;
+        ent.check_against = EntityGroup::NONE;
+        ent.gravity = 0.0;
+        ent.anim = Some(self.anim.clone());
     }
 
-    fn kill(&mut self, eng: &mut Engine, _ent: &mut Entity) {
-        eprintln!("Player dead... reload level");
-        G.with_borrow_mut(|g| {
-            g.dead += 1;
-            g.loading_level = Some(g.current_level);
+    fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        self.timer += eng.tick;
+        let Some((pos, size)) = G.with_borrow(|g| g.ghost.as_ref()?.sample(self.timer)) else {
+            eng.kill(ent.ent_ref);
+            return;
+        };
+        ent.pos = pos;
+        ent.size = size;
+        let hidden = SAVE.with_borrow(|save| save.progress.hide_ghost);
+        ent.anim = (!hidden).then(|| {
+            let mut anim = self.anim.clone();
+            anim.sheet.size = UVec2::new(size.x as u32, size.y as u32);
+            anim
         });
-        S.with_borrow_mut(|sound| sound.play_killed(eng));
     }
 }
 
-pub struct Loading {
-    handle: Handle,
+#[derive(Clone)]
+pub struct Player {
+    balloon: Balloon,
+    anim: Animation,
+    size: Vec2,
+    /// Set before the player kills itself, other entities only kill with spikes
+    cause: Option<DeathCause>,
 }
 
-impl Scene for Loading {
-    fn update(&mut self, eng: &mut Engine) {
-        log::info!("Loading....");
-        if let Some(data) = eng.assets.get_raw(&self.handle) {
-            PROJ.with_borrow_mut(|proj| {
-                *proj = serde_json::from_slice(data).unwrap();
-            });
+impl EntityType for Player {
+    fn load(eng: &mut Engine) -> Self {
+        let balloon = Balloon::default();
+        let size = balloon.size().min(PLAYER_SIZE);
+        let texture = load_texture(eng, "ball.png");
+        let sheet = Sprite::with_sizef(texture, size);
+        let anim = Animation::new(sheet);
+
+        Self {
+            balloon,
+            anim,
+            size,
+            cause: None,
+        }
+    }
+    fn init(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        // init items
+        let respawn = G.with_borrow_mut(|g| {
+            g.air = AirTank::empty(g.air_capacity());
+            if let Some(pos) = g.spawn_point {
+                ent.pos = pos;
+            }
+            self.balloon = Balloon::new(g.tuning);
+            g.respawn.clone()
+        });
+        self.size = self.balloon.size().min(PLAYER_SIZE);
+        self.anim.sheet.size = UVec2::new(self.size.x as u32, self.size.y as u32);
+
+        ent.check_against = EntityGroup::ITEM;
+        ent.physics = EntityPhysics::ACTIVE;
+        ent.group = EntityGroup::PLAYER;
+        // above the ghost
+        ent.draw_order = 1;
+        ent.gravity = 1.0;
+        ent.mass = 1.0;
+        ent.size = self.size;
+        ent.anim = Some(self.anim.clone());
 
-            eng.set_scene(Demo::default());
+        // back at the last checkpoint, the air is restored with the level
+        if let Some(respawn) = respawn {
+            if let Some(size) = respawn.original_size {
+                self.balloon.original_size = Vec2::from(size);
+                let texture = load_texture(eng, "ball-king.png");
+                ent.anim = Some(Animation::new(Sprite::with_sizef(
+                    texture,
+                    Vec2::from(size),
+                )));
+            }
+            let mut body = Body::from_entity(ent);
+            body.pos = respawn.pos();
+            self.balloon
+                .set_inflation_rate(respawn.inflation, &mut body);
+            body.apply(ent);
+            if let Some(anim) = ent.anim.as_mut() {
+                anim.sheet.size = UVec2::new(body.size.x as u32, body.size.y as u32);
+            }
+        }
+
+        // set camera
+        let cam = eng.camera_mut();
+        cam.follow(ent.ent_ref, true);
+        cam.speed = 3.;
+        cam.min_vel = Vec2::splat(5.);
+    }
+
+    fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        let mut body = Body::from_entity(ent);
+        // the player's own entity is borrowed and skipped, open gates aren't solid
+        let solids: Vec<Rect> = eng
+            .world()
+            .entities()
+            .filter_map(|ent| {
+                let ent = ent.try_borrow().ok()?;
+                let solid = ent.ent_type.is::<MovingPlatform>() || ent.ent_type.is::<Gate>();
+                (solid && !ent.physics.is_empty()).then(|| ent.bounds())
+            })
+            .collect();
+        let step = G.with_borrow_mut(|g| {
+            self.balloon.set_tuning(g.tuning);
+            self.balloon.step(
+                &g.controls,
+                &mut body,
+                &mut g.air,
+                eng.collision_map.as_ref(),
+                &solids,
+                eng.tick,
+            )
+        });
+        let wind: Vec2 = G.with_borrow(|g| g.winds.iter().map(|w| w.accel(&body)).sum());
+        body.accel += wind;
+        body.apply(ent);
+        G.with_borrow_mut(|g| g.player = Some(body));
+
+        let bounds = eng.collision_map.as_ref().map(|map| map.bounds());
+        if bounds.is_some_and(|bounds| out_of_bounds(&body, bounds)) {
+            self.cause.get_or_insert(DeathCause::OutOfBounds);
+            eng.kill(ent.ent_ref);
+            return;
+        }
+
+        if step.restart || step.burst {
+            let cause = if step.burst {
+                DeathCause::Popped
+            } else {
+                DeathCause::Restart
+            };
+            self.cause.get_or_insert(cause);
+            eng.kill(ent.ent_ref);
+            return;
+        }
+
+        match step.sound {
+            Some(BalloonSound::Inflate) => emit(GameEvent::Inflating),
+            Some(BalloonSound::Deflate) => emit(GameEvent::Deflating),
+            Some(BalloonSound::Silence) => emit(GameEvent::AirStopped),
+            None => {}
+        }
+
+        if let Some(anim) = ent.anim.as_mut() {
+            // Scale sprite image
+            if step.resized {
+                anim.sheet.size = UVec2::new(body.size.x as u32, body.size.y as u32);
+            }
+            if let Some(flip_x) = step.flip_x {
+                anim.sheet.flip_x = flip_x;
+            }
+            // redden with the pressure
+            let relief = 255 - (self.balloon.pressure().min(1.0) * 155.0) as u8;
+            anim.sheet.color = Color::rgb(255, relief, relief);
+        }
+    }
+
+    fn collide(&mut self, eng: &mut Engine, ent: &mut Entity, normal: Vec2, trace: Option<&Trace>) {
+        let tile = trace.and_then(|t| eng.collision_map.as_ref()?.get(t.tile_pos));
+        if tile.is_some_and(|tile| self.balloon.pops_on(tile)) {
+            self.cause.get_or_insert(DeathCause::Popped);
+            eng.kill(ent.ent_ref);
+        }
+
+        let speed = ent.vel.x.abs() + ent.vel.y.abs();
+        if !self.balloon.can_jump && speed > IMPACT_MIN_SPEED {
+            // the normal points away from the wall, the balloon touches it on the other side
+            let contact = ent.pos.x - normal.x * ent.size.x * 0.5;
+            let level_width = eng.collision_map.as_ref().map_or(0.0, |map| map.bounds().x);
+            emit(GameEvent::Bounced(Impact {
+                speed,
+                size: self.balloon.inflation_share(),
+                panning: stereo_panning(contact, ent.pos.x, screen_size(eng).x, level_width),
+            }));
+        }
+    }
+
+    fn touch(&mut self, eng: &mut Engine, ent: &mut Entity, other: &mut Entity) {
+        if other.ent_type.is::<Checkpoint>() {
+            let Some(iid) = entity_iid(other.ent_ref) else {
+                return;
+            };
+            let reached = G.with_borrow_mut(|g| {
+                if g.respawn.as_ref().is_some_and(|r| r.checkpoint == iid) {
+                    return false;
+                }
+                g.respawn = Some(Respawn {
+                    checkpoint: iid,
+                    pos: ent.pos.into(),
+                    inflation: self.balloon.inflation_rate(),
+                    original_size: (self.balloon.original_size != PLAYER_SIZE)
+                        .then(|| self.balloon.original_size.into()),
+                    air: g.air.amount,
+                    removed: g.removed.clone(),
+                });
+                true
+            });
+            if reached {
+                emit(GameEvent::CheckpointReached);
+            }
+            return;
+        }
+        if other.ent_type.is::<Crown>() {
+            eng.kill(other.ent_ref);
+            consume(other, RespawnPolicy::Keep);
+            emit(GameEvent::ItemPickedUp);
+
+            self.balloon.original_size *= 2.0;
+            let size = self.balloon.size().min(self.balloon.original_size);
+            ent.size = size;
+            let texture = load_texture(eng, "ball-king.png");
+            let sheet = Sprite::with_sizef(texture, size);
+            ent.anim = Some(Animation::new(sheet));
+        }
+    }
+
+    fn kill(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        let cause = self.cause.take().unwrap_or(DeathCause::Spikes);
+        eprintln!("Player dead ({cause:?})... reload level");
+        let (level, replaying) = G.with_borrow_mut(|g| {
+            g.dead += 1;
+            g.death.kill(cause);
+            g.deaths.record(&g.current_level, cause);
+            (g.current_level.clone(), g.replaying)
+        });
+        if !replaying {
+            SAVE.with_borrow_mut(|save| save.record_death(&level));
+        }
+        // the level reloads once the death animation is over
+        eng.spawn::<DeathEffect>(ent.pos);
+        let shreds = if cause == DeathCause::Popped { 12 } else { 6 };
+        for _ in 0..shreds {
+            eng.spawn::<Shred>(ent.pos);
+        }
+        if cause == DeathCause::Popped {
+            emit(GameEvent::Popped);
+        } else {
+            emit(GameEvent::PlayerDied);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DeathEffect {
+    anim: Animation,
+}
+
+impl EntityType for DeathEffect {
+    fn load(eng: &mut Engine) -> Self {
+        let texture = load_texture(eng, "ball-death.png");
+        let sheet = Sprite::with_sizef(texture, Vec2::new(28.0, 24.0));
+        let anim = Animation::new(sheet);
+        Self { anim }
+    }
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        let size = G.with_borrow(|g| g.player.map_or(PLAYER_SIZE, |body| body.size));
+        ent.size = size;
+        self.anim.sheet.size = UVec2::new(size.x as u32, size.y as u32);
+        ent.anim = Some(self.anim.clone());
+        ent.draw_order = 1;
+        ent.gravity = 0.;
+    }
+    fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        let (cause, t) = G.with_borrow(|g| (g.death.cause(), g.death.progress()));
+        let Some(cause) = cause else {
+            eng.kill(ent.ent_ref);
+            return;
+        };
+        // a popped balloon blows up, other deaths shrink away
+        let scale = if cause == DeathCause::Popped {
+            1.0 + t * 0.8
+        } else {
+            1.0 - t * 0.5
+        };
+        ent.scale = Vec2::splat(scale);
+        if let Some(anim) = ent.anim.as_mut() {
+            let alpha = ((1.0 - t) * 255.0) as u8;
+            anim.sheet.color = if cause == DeathCause::Popped {
+                Color::rgba(255, 110, 110, alpha)
+            } else {
+                Color::rgba(255, 255, 255, alpha)
+            };
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Shred {
+    anim: Animation,
+    timer: f32,
+}
+
+impl Shred {
+    const LIFETIME: f32 = 0.6;
+}
+
+impl EntityType for Shred {
+    fn load(eng: &mut Engine) -> Self {
+        let texture = load_texture(eng, "ball.png");
+        let sheet = Sprite::with_sizef(texture, Vec2::splat(6.0));
+        let anim = Animation::new(sheet);
+        Self { anim, timer: 0.0 }
+    }
+    fn init(&mut self, _eng: &mut Engine, ent: &mut Entity) {
+        ent.size = Vec2::splat(6.0);
+        ent.anim = Some(self.anim.clone());
+        ent.physics = EntityPhysics::MOVE;
+        ent.draw_order = 1;
+        ent.gravity = 0.5;
+        ent.vel = RNG.with_borrow_mut(|rng| {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            Vec2::from_angle(angle) * rng.gen_range(80.0..220.0)
+        });
+    }
+    fn update(&mut self, eng: &mut Engine, ent: &mut Entity) {
+        self.timer += eng.tick;
+        if self.timer >= Self::LIFETIME {
+            eng.kill(ent.ent_ref);
+            return;
+        }
+        if let Some(anim) = ent.anim.as_mut() {
+            let alpha = ((1.0 - self.timer / Self::LIFETIME) * 255.0) as u8;
+            anim.sheet.color = Color::rgba(255, 255, 255, alpha);
+        }
+    }
+}
+
+pub struct Loading {
+    handle: Handle,
+    replay: Option<Replay>,
+}
+
+impl Scene for Loading {
+    fn update(&mut self, eng: &mut Engine) {
+        log::info!("Loading....");
+        let tuning_loaded = TUNING.with_borrow_mut(|tuning| {
+            tuning.update(eng);
+            tuning.loaded
+        });
+        if !tuning_loaded {
+            return;
+        }
+        if let Some(data) = eng.assets.get_raw(&self.handle) {
+            PROJ.with_borrow_mut(|proj| {
+                *proj = serde_json::from_slice(data).unwrap();
+            });
+
+            match self.replay.take() {
+                Some(replay) => eng.set_scene(Demo::replay(&replay)),
+                None => eng.set_scene(LevelSelect::default()),
+            }
+        }
+    }
+}
+
+const MENU_ROW_HEIGHT: f32 = 40.0;
+const MENU_VISIBLE_ROWS: usize = 9;
+
+#[derive(Default)]
+pub struct Menu {
+    items: Vec<String>,
+    selected: usize,
+    texts: Vec<Sprite>,
+}
+
+impl Menu {
+    fn new(items: &[&str]) -> Self {
+        Self {
+            items: items.iter().map(|item| item.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn set_item(&mut self, index: usize, item: String) {
+        self.items[index] = item;
+        // render again on the next update
+        self.texts.clear();
+    }
+
+    fn update(&mut self, eng: &mut Engine) -> Option<usize> {
+        let input = read_controls(eng);
+        let mut changed = self.texts.is_empty();
+        if input.just_pressed(Action::Up) && self.selected > 0 {
+            self.selected -= 1;
+            changed = true;
+        } else if input.just_pressed(Action::Down) && self.selected + 1 < self.items.len() {
+            self.selected += 1;
+            changed = true;
+        }
+        let chosen = (input.just_pressed(Action::Jump) || input.just_pressed(Action::Confirm))
+            .then_some(self.selected);
+
+        if changed {
+            if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+                self.texts = self
+                    .items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        let color = if i == self.selected { HIGHLIGHT } else { WHITE };
+                        text_sprite(eng, font.clone(), item.clone(), 28.0, color)
+                    })
+                    .collect();
+            }
+        }
+        chosen
+    }
+
+    fn draw(&self, eng: &mut Engine, mut pos: Vec2) {
+        let first = (self.selected + 1).saturating_sub(MENU_VISIBLE_ROWS);
+        for text in self.texts.iter().skip(first).take(MENU_VISIBLE_ROWS) {
+            eng.draw_image(text, pos, None, None);
+            pos.y += MENU_ROW_HEIGHT;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Capture {
+    /// The key that opened the capture is still held
+    Opening(Action),
+    Listening(Action),
+    /// Bind once released so the input state stays consistent
+    Held(Action, KeyCode),
+}
+
+pub struct ControlsMenu {
+    menu: Menu,
+    capture: Option<Capture>,
+    message: Option<Sprite>,
+}
+
+impl ControlsMenu {
+    const RESET: usize = Action::ALL.len();
+    const BACK: usize = Action::ALL.len() + 1;
+
+    fn new() -> Self {
+        let mut items: Vec<String> = Action::ALL.into_iter().map(Self::action_label).collect();
+        items.push("Reset Defaults".to_string());
+        items.push("Back".to_string());
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        Self {
+            menu: Menu::new(&items),
+            capture: None,
+            message: None,
+        }
+    }
+
+    fn action_label(action: Action) -> String {
+        let keys = BINDINGS.with_borrow(|bindings| bindings.keys(action));
+        let keys: Vec<String> = keys.into_iter().map(key_name).collect();
+        format!("{}: {}", action.label(), keys.join(", "))
+    }
+
+    fn relabel(&mut self) {
+        for (i, action) in Action::ALL.into_iter().enumerate() {
+            self.menu.set_item(i, Self::action_label(action));
+        }
+    }
+
+    fn show_message(&mut self, eng: &mut Engine, content: String) {
+        if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+            self.message
+                .replace(text_sprite(eng, font, content, 20.0, GRAY));
+        }
+    }
+
+    fn finish_capture(&mut self, eng: &mut Engine, action: Action, key: KeyCode) {
+        let name = key_name(key);
+        let label = action.label();
+        let content = match key {
+            KeyCode::Escape => "Cancelled".to_string(),
+            KeyCode::BackSpace => {
+                match BINDINGS.with_borrow_mut(|bindings| bindings.unbind_last(action)) {
+                    Some(key) => format!("{} removed from {label}", key_name(key)),
+                    None => format!("{label} needs a key"),
+                }
+            }
+            _ => match BINDINGS.with_borrow_mut(|bindings| bindings.bind(action, key)) {
+                Rebind::Added => format!("{name} added to {label}"),
+                Rebind::Unchanged => format!("{name} already triggers {label}"),
+                Rebind::Moved(other) => format!("{name} moved from {} to {label}", other.label()),
+                Rebind::Conflict(other) => {
+                    format!("{name} is the only key of {}", other.label())
+                }
+            },
+        };
+        BINDINGS.with_borrow(|bindings| {
+            bindings.save();
+            bindings.apply(eng.input_mut());
+        });
+        self.relabel();
+        self.show_message(eng, content);
+    }
+
+    fn update(&mut self, eng: &mut Engine) -> bool {
+        if let Some(capture) = self.capture {
+            let input = eng.input();
+            match capture {
+                Capture::Opening(action) => {
+                    if Action::ALL.into_iter().all(|action| !input.pressed(action)) {
+                        capture_keys(eng.input_mut());
+                        self.capture = Some(Capture::Listening(action));
+                    }
+                }
+                Capture::Listening(action) => {
+                    if let Some(key) = bindable_keys().find(|&key| input.just_pressed(raw_key(key)))
+                    {
+                        self.capture = Some(Capture::Held(action, key));
+                    }
+                }
+                Capture::Held(action, key) => {
+                    if !input.pressed(raw_key(key)) {
+                        self.capture = None;
+                        self.finish_capture(eng, action, key);
+                    }
+                }
+            }
+            return false;
+        }
+
+        match self.menu.update(eng) {
+            Some(Self::RESET) => {
+                BINDINGS.with_borrow_mut(|bindings| {
+                    *bindings = Bindings::default();
+                    bindings.save();
+                    bindings.apply(eng.input_mut());
+                });
+                self.relabel();
+                self.show_message(eng, "Restored the default keys".to_string());
+                false
+            }
+            Some(Self::BACK) => true,
+            Some(i) => {
+                let action = Action::ALL[i];
+                self.capture = Some(Capture::Opening(action));
+                let content = format!("Press a key for {}, Backspace removes one", action.label());
+                self.show_message(eng, content);
+                false
+            }
+            None => read_controls(eng).just_pressed(Action::Pause),
+        }
+    }
+
+    fn draw(&self, eng: &mut Engine, pos: Vec2) {
+        self.menu.draw(eng, pos);
+        if let Some(text) = self.message.as_ref() {
+            let y = pos.y + MENU_ROW_HEIGHT * MENU_VISIBLE_ROWS as f32;
+            eng.draw_image(text, Vec2::new(pos.x, y), None, None);
+        }
+    }
+}
+
+pub struct Settings {
+    menu: Menu,
+    controls: Option<ControlsMenu>,
+}
+
+impl Settings {
+    const SHOW_GHOST: usize = 0;
+    const CONTROLS: usize = 1;
+    const FIRST_VOLUME: usize = 2;
+    const VOLUMES: [Bus; 3] = [Bus::Master, Bus::Sfx, Bus::Music];
+    const MUTE: usize = 5;
+    const SOUND: usize = 6;
+    const BACK: usize = 7;
+
+    fn new() -> Self {
+        let mut items = vec![Self::ghost_label(), "Controls".to_string()];
+        items.extend(Self::VOLUMES.map(Self::volume_label));
+        items.push(Self::mute_label());
+        items.push(Self::sound_label());
+        items.push("Back".to_string());
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        Self {
+            menu: Menu::new(&items),
+            controls: None,
+        }
+    }
+
+    fn volume_label(bus: Bus) -> String {
+        let volume = S.with_borrow(|sound| sound.audio_settings().volume(bus));
+        format!("{}: {}%", bus.label(), (volume * 100.0).round())
+    }
+
+    fn mute_label() -> String {
+        let muted = S.with_borrow(|sound| sound.audio_settings().muted);
+        format!("Mute: {}", if muted { "on" } else { "off" })
+    }
+
+    fn sound_label() -> String {
+        if S.with_borrow(|sound| sound.is_unavailable()) {
+            "Audio device: missing, retry".to_string()
+        } else {
+            "Audio device: ok".to_string()
+        }
+    }
+
+    fn step_volume(&mut self, index: usize, steps: i32) {
+        let bus = Self::VOLUMES[index - Self::FIRST_VOLUME];
+        S.with_borrow_mut(|sound| {
+            let mut settings = sound.audio_settings();
+            settings.step(bus, steps);
+            sound.set_audio_settings(settings);
+        });
+        self.menu.set_item(index, Self::volume_label(bus));
+    }
+
+    fn ghost_label() -> String {
+        let hidden = SAVE.with_borrow(|save| save.progress.hide_ghost);
+        format!("Ghost: {}", if hidden { "off" } else { "on" })
+    }
+
+    fn update(&mut self, eng: &mut Engine) -> bool {
+        if let Some(controls) = self.controls.as_mut() {
+            if controls.update(eng) {
+                self.controls = None;
+            }
+            return false;
+        }
+        let selected = self.menu.selected();
+        let volume_row = (Self::FIRST_VOLUME..Self::MUTE).contains(&selected);
+        let controls = read_controls(eng);
+        if volume_row && controls.just_pressed(Action::Left) {
+            self.step_volume(selected, -1);
+        } else if volume_row && controls.just_pressed(Action::Right) {
+            self.step_volume(selected, 1);
+        }
+        match self.menu.update(eng) {
+            Some(Self::CONTROLS) => {
+                self.controls = Some(ControlsMenu::new());
+                false
+            }
+            // volumes only change with Left/Right so a stray confirm can't mute a bus
+            Some(i) if (Self::FIRST_VOLUME..Self::MUTE).contains(&i) => false,
+            Some(Self::MUTE) => {
+                S.with_borrow_mut(|sound| {
+                    let mut settings = sound.audio_settings();
+                    settings.muted = !settings.muted;
+                    sound.set_audio_settings(settings);
+                });
+                self.menu.set_item(Self::MUTE, Self::mute_label());
+                false
+            }
+            Some(Self::SOUND) => {
+                if S.with_borrow(|sound| sound.is_unavailable())
+                    && S.with_borrow_mut(|sound| sound.retry_audio())
+                {
+                    log::info!("Audio device opened");
+                }
+                self.menu.set_item(Self::SOUND, Self::sound_label());
+                false
+            }
+            Some(Self::SHOW_GHOST) => {
+                SAVE.with_borrow_mut(|save| {
+                    save.progress.hide_ghost = !save.progress.hide_ghost;
+                    save.save();
+                });
+                self.menu.set_item(Self::SHOW_GHOST, Self::ghost_label());
+                false
+            }
+            Some(Self::BACK) => true,
+            _ => read_controls(eng).just_pressed(Action::Pause),
+        }
+    }
+
+    fn draw(&self, eng: &mut Engine, pos: Vec2) {
+        match self.controls.as_ref() {
+            Some(controls) => controls.draw(eng, pos),
+            None => self.menu.draw(eng, pos),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseItem {
+    Resume,
+    RestartLevel,
+    LevelSelect,
+    Settings,
+    Quit,
+}
+
+impl PauseItem {
+    fn label(self) -> &'static str {
+        match self {
+            PauseItem::Resume => "Resume",
+            PauseItem::RestartLevel => "Restart Level",
+            PauseItem::LevelSelect => "Level Select",
+            PauseItem::Settings => "Settings",
+            PauseItem::Quit => "Quit",
+        }
+    }
+}
+
+pub struct Pause {
+    items: Vec<PauseItem>,
+    menu: Menu,
+    settings: Option<Settings>,
+    title_text: Option<Sprite>,
+}
+
+impl Pause {
+    fn new() -> Self {
+        let mut items = vec![
+            PauseItem::Resume,
+            PauseItem::RestartLevel,
+            PauseItem::LevelSelect,
+            PauseItem::Settings,
+        ];
+        // the browser tab is closed instead
+        if cfg!(not(target_arch = "wasm32")) {
+            items.push(PauseItem::Quit);
+        }
+        let labels: Vec<&str> = items.iter().map(|item| item.label()).collect();
+        Self {
+            menu: Menu::new(&labels),
+            items,
+            settings: None,
+            title_text: None,
+        }
+    }
+
+    /// Handle input, return the chosen item, `Resume` when Pause is pressed again
+    fn update(&mut self, eng: &mut Engine) -> Option<PauseItem> {
+        if self.title_text.is_none() {
+            if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+                self.title_text
+                    .replace(text_sprite(eng, font, "Paused".to_string(), 36.0, WHITE));
+            }
+        }
+        if let Some(settings) = self.settings.as_mut() {
+            if settings.update(eng) {
+                self.settings = None;
+            }
+            return None;
+        }
+        if read_controls(eng).just_pressed(Action::Pause) {
+            return Some(PauseItem::Resume);
+        }
+        let item = self.items[self.menu.update(eng)?];
+        if item == PauseItem::Settings {
+            self.settings = Some(Settings::new());
+        }
+        Some(item)
+    }
+
+    fn draw(&self, eng: &mut Engine) {
+        let center_x = eng.view_size().x * 0.5;
+        if let Some(text) = self.title_text.as_ref() {
+            eng.draw_image(text, Vec2::new(center_x, MENU_ROW_HEIGHT * 1.5), None, None);
+        }
+        let pos = Vec2::new(center_x, MENU_ROW_HEIGHT * 3.0);
+        match self.settings.as_ref() {
+            Some(settings) => settings.draw(eng, pos),
+            None => self.menu.draw(eng, pos),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LevelSelect {
+    levels: Vec<String>,
+    selected: usize,
+    title_text: Option<Sprite>,
+    level_texts: Vec<Sprite>,
+    hint_text: Option<Sprite>,
+}
+
+impl LevelSelect {
+    fn render_texts(&mut self, eng: &mut Engine, font: Font) {
+        let slot = SAVE.with_borrow(|save| save.slot);
+        self.title_text.replace(text_sprite(
+            eng,
+            font.clone(),
+            format!("Select Level  < Slot {} >", slot + 1),
+            36.0,
+            WHITE,
+        ));
+        self.hint_text.replace(text_sprite(
+            eng,
+            font.clone(),
+            "Left/Right to switch slots, R to watch the last attempt".to_string(),
+            20.0,
+            GRAY,
+        ));
+        let progress = SAVE.with_borrow(|save| save.progress.clone());
+        self.level_texts = self
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(i, identifier)| {
+                let record = progress.levels.get(identifier);
+                let (status, color) = if !is_unlocked(&progress, identifier) {
+                    ("locked".to_string(), GRAY)
+                } else if let Some(best_time) = record.and_then(|r| r.best_time) {
+                    (format!("best {best_time:.2}s"), WHITE)
+                } else {
+                    ("new".to_string(), WHITE)
+                };
+                let color = if i == self.selected { HIGHLIGHT } else { color };
+                let content = format!("{identifier}  {status}");
+                text_sprite(eng, font.clone(), content, 28.0, color)
+            })
+            .collect();
+    }
+}
+
+impl Scene for LevelSelect {
+    fn init(&mut self, eng: &mut Engine) {
+        bind_keys(eng.input_mut());
+        self.levels = PROJ.with_borrow(project_levels);
+        self.selected = G.with_borrow(|g| {
+            self.levels
+                .iter()
+                .position(|level| *level == g.current_level)
+                .unwrap_or_default()
+        });
+    }
+
+    fn update(&mut self, eng: &mut Engine) {
+        eng.scene_base_update();
+        update_touch(eng);
+
+        let input = read_controls(eng);
+        let last = self.levels.len().saturating_sub(1);
+        let mut changed = false;
+        if input.just_pressed(Action::Up) && self.selected > 0 {
+            self.selected -= 1;
+            changed = true;
+        } else if input.just_pressed(Action::Down) && self.selected < last {
+            self.selected += 1;
+            changed = true;
+        }
+        let step = if input.just_pressed(Action::Right) {
+            1
+        } else if input.just_pressed(Action::Left) {
+            SAVE_SLOTS - 1
+        } else {
+            0
+        };
+        if step != 0 {
+            SAVE.with_borrow_mut(|save| save.load((save.slot + step) % SAVE_SLOTS));
+            changed = true;
+        }
+
+        if input.just_pressed(Action::Jump) || input.just_pressed(Action::Confirm) {
+            if let Some(level) = self.levels.get(self.selected).cloned() {
+                if SAVE.with_borrow(|save| is_unlocked(&save.progress, &level)) {
+                    G.with_borrow_mut(|g| g.start_level(level));
+                    eng.set_scene(Demo::default());
+                    return;
+                }
+            }
+        }
+
+        if input.just_pressed(Action::Restart) {
+            if let Some(replay) = replay::load_last() {
+                eng.set_scene(Demo::replay(&replay));
+                return;
+            }
+        }
+
+        if changed || self.level_texts.is_empty() {
+            if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+                self.render_texts(eng, font);
+            }
+        }
+    }
+
+    fn draw(&mut self, eng: &mut Engine) {
+        eng.scene_base_draw();
+        let center_x = eng.view_size().x * 0.5;
+        if let Some(text) = self.title_text.as_ref() {
+            eng.draw_image(text, Vec2::new(center_x, MENU_ROW_HEIGHT * 1.5), None, None);
+        }
+        // keep the selected level in the visible window
+        let first = (self.selected + 1).saturating_sub(MENU_VISIBLE_ROWS);
+        let mut y = MENU_ROW_HEIGHT * 3.0;
+        for text in self.level_texts.iter().skip(first).take(MENU_VISIBLE_ROWS) {
+            eng.draw_image(text, Vec2::new(center_x, y), None, None);
+            y += MENU_ROW_HEIGHT;
+        }
+        if let Some(text) = self.hint_text.as_ref() {
+            let pos = Vec2::new(center_x, eng.view_size().y - MENU_ROW_HEIGHT * 0.5);
+            eng.draw_image(text, pos, None, None);
+        }
+        TOUCH.with_borrow(|touch| touch.draw(eng));
+    }
+}
+
+pub struct Results {
+    deaths: usize,
+    time: f32,
+    splits: Splits,
+    /// Personal best before this run
+    pb: Option<Splits>,
+    texts: Vec<Sprite>,
+    menu: Menu,
+}
+
+impl Results {
+    const RESTART: usize = 0;
+    const LEVEL_SELECT: usize = 1;
+    const EXPORT_SPLITS: usize = 2;
+
+    pub fn new(deaths: usize, time: f32, splits: Splits, pb: Option<Splits>) -> Self {
+        Self {
+            deaths,
+            time,
+            splits,
+            pb,
+            texts: Vec::new(),
+            menu: Menu::new(&["Restart", "Level Select", "Export Splits"]),
+        }
+    }
+}
+
+impl Scene for Results {
+    fn update(&mut self, eng: &mut Engine) {
+        eng.scene_base_update();
+        update_touch(eng);
+
+        if self.texts.is_empty() {
+            if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+                self.texts = vec![
+                    text_sprite(eng, font.clone(), "You made it!".to_string(), 36.0, WHITE),
+                    text_sprite(
+                        eng,
+                        font.clone(),
+                        format!("Deaths {}", self.deaths),
+                        28.0,
+                        GRAY,
+                    ),
+                    text_sprite(
+                        eng,
+                        font.clone(),
+                        format!("Time {}", format_time(self.time)),
+                        28.0,
+                        GRAY,
+                    ),
+                ];
+                if let Some(pb) = self.pb.as_ref() {
+                    let content = format!("Best {}", format_time(pb.total()));
+                    self.texts.push(text_sprite(eng, font, content, 28.0, GRAY));
+                }
+            }
+        }
+
+        match self.menu.update(eng) {
+            Some(Self::RESTART) => {
+                G.with_borrow_mut(|g| {
+                    g.dead = 0;
+                    g.deaths = DeathTally::default();
+                    g.start_level(level_identifier(0));
+                });
+                eng.set_scene(Demo::default());
+            }
+            Some(Self::LEVEL_SELECT) => {
+                eng.set_scene(LevelSelect::default());
+            }
+            Some(Self::EXPORT_SPLITS) => match splits::export(&self.splits, self.pb.as_ref()) {
+                Ok(_) => log::info!("Exported splits"),
+                Err(err) => log::error!("Can't export splits err {err:?}"),
+            },
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, eng: &mut Engine) {
+        eng.scene_base_draw();
+        let center_x = eng.view_size().x * 0.5;
+        let mut y = MENU_ROW_HEIGHT * 2.0;
+        for text in &self.texts {
+            eng.draw_image(text, Vec2::new(center_x, y), None, None);
+            y += MENU_ROW_HEIGHT;
+        }
+        self.menu
+            .draw(eng, Vec2::new(center_x, y + MENU_ROW_HEIGHT * 2.0));
+        TOUCH.with_borrow(|touch| touch.draw(eng));
+    }
+}
+
+pub struct TouchOverlay {
+    layout: TouchLayout,
+    visible: bool,
+    controls: Controls,
+    labels: Vec<Sprite>,
+}
+
+impl Default for TouchOverlay {
+    fn default() -> Self {
+        Self {
+            layout: TouchLayout::new(VIEW_SIZE),
+            visible: is_touch_device(),
+            controls: Controls::default(),
+            labels: Vec::new(),
+        }
+    }
+}
+
+impl TouchOverlay {
+    const COLOR: Color = Color::rgba(0x80, 0x80, 0x80, 0x80);
+
+    fn update(&mut self, eng: &mut Engine) {
+        // the window can change its aspect ratio at any time
+        self.layout = TouchLayout::new(screen_size(eng));
+        let touches = touches();
+        self.visible |= !touches.is_empty();
+        self.controls = self.layout.controls(&touches, &self.controls);
+        if self.visible && self.labels.is_empty() {
+            if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+                let layout = &self.layout;
+                self.labels = layout
+                    .arrows
+                    .iter()
+                    .chain(&layout.buttons)
+                    .map(|button| {
+                        text_sprite(eng, font.clone(), button.label.to_string(), 24.0, WHITE)
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    fn draw(&self, eng: &mut Engine) {
+        if !self.visible {
+            return;
+        }
+        let texture = load_texture(eng, "ball.png");
+        let mut ball = Sprite::with_sizef(texture, PLAYER_SIZE);
+        ball.color = Self::COLOR;
+        let layout = &self.layout;
+        let scale = Vec2::splat(layout.dpad_radius * 2.0) / PLAYER_SIZE;
+        eng.draw_image(&ball, layout.dpad_center, Some(scale), None);
+
+        for (i, button) in layout.arrows.iter().chain(&layout.buttons).enumerate() {
+            ball.color = if self.controls.pressed(button.action) {
+                HIGHLIGHT
+            } else {
+                Self::COLOR
+            };
+            let scale = Vec2::splat(button.radius * 2.0) / PLAYER_SIZE;
+            eng.draw_image(&ball, button.center, Some(scale), None);
+            if let Some(label) = self.labels.get(i) {
+                eng.draw_image(label, button.center, None, None);
+            }
         }
     }
 }
@@ -519,8 +2534,22 @@ impl Scene for Loading {
 pub struct Demo {
     frames: f32,
     timer: f32,
+    level_timer: f32,
     dead_text: Option<Sprite>,
-    remained_air_text: Option<Sprite>,
+    air_text: Option<Sprite>,
+    recording: Option<Replay>,
+    playback: Option<Playback>,
+    /// Trajectory of the current attempt, becomes the ghost if it beats the best run
+    ghost_run: GhostRun,
+    splits: Splits,
+    pb: Option<Splits>,
+    /// Run time when the current level was entered
+    split_start: f32,
+    timer_text: Option<Sprite>,
+    split_text: Option<Sprite>,
+    pause: Option<Pause>,
+    /// The attempt went on from a checkpoint, it can't become the ghost
+    resumed: bool,
 }
 
 impl Default for Demo {
@@ -528,76 +2557,380 @@ impl Default for Demo {
         Self {
             frames: 0.0,
             timer: 0.0,
+            level_timer: 0.0,
             dead_text: None,
-            remained_air_text: None,
+            air_text: None,
+            recording: None,
+            playback: None,
+            ghost_run: GhostRun::default(),
+            splits: Splits::default(),
+            pb: None,
+            split_start: 0.0,
+            timer_text: None,
+            split_text: None,
+            pause: None,
+            resumed: false,
+        }
+    }
+}
+
+impl Demo {
+    pub fn replay(replay: &Replay) -> Self {
+        G.with_borrow_mut(|g| {
+            g.start_level(replay.level.clone());
+            g.spawn_point = replay.spawn_point.map(Vec2::from);
+            g.respawn = replay.checkpoint.clone();
+            g.upgrades = replay.upgrades.clone();
+        });
+        RNG.set(StdRng::seed_from_u64(replay.seed));
+        Self {
+            playback: Some(Playback::new(replay)),
+            ..Default::default()
+        }
+    }
+
+    fn start_recording(&mut self, level: &str) {
+        let seed = rand::random();
+        RNG.set(StdRng::seed_from_u64(seed));
+        let mut replay = Replay::new(level.to_string(), seed);
+        G.with_borrow(|g| {
+            replay.spawn_point = g.spawn_point.map(Into::into);
+            replay.checkpoint = g.respawn.clone();
+            replay.upgrades = g.upgrades.clone();
+        });
+        replay.tuning = Some(level_tuning(level));
+        self.recording = Some(replay);
+    }
+
+    /// Replays keep the tuning they were recorded with
+    fn tuning(&self, level: &str) -> Tuning {
+        self.playback
+            .as_ref()
+            .and_then(Playback::tuning)
+            .unwrap_or_else(|| level_tuning(level))
+    }
+
+    fn finish_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            replay::save_last(&recording);
+        }
+    }
+
+    fn enter_level(&mut self, eng: &mut Engine, level: &str) -> Result<()> {
+        PROJ.with_borrow(|proj| proj.get_level(level).map(|_| ()))?;
+        apply_tuning(eng, self.tuning(level));
+        load_level(eng, level)?;
+        let music = PROJ.with_borrow(|proj| proj.get_level(level).ok().and_then(level_music));
+        S.with_borrow_mut(|sound| sound.play_music(eng, music));
+        self.ghost_run = GhostRun::default();
+        let slot = SAVE.with_borrow(|save| save.slot);
+        let ghost = load_ghost(slot, level);
+        if let Some((pos, _)) = ghost.as_ref().and_then(|run| run.sample(0.0)) {
+            eng.spawn::<Ghost>(pos);
+        }
+        // entities consumed before the checkpoint stay consumed
+        let removed = G.with_borrow_mut(|g| {
+            g.ghost = ghost;
+            g.player = None;
+            g.death = DeathState::Alive;
+            g.removed = g
+                .respawn
+                .as_ref()
+                .map(|respawn| respawn.removed.clone())
+                .unwrap_or_default();
+            g.removed
+                .iter()
+                .filter_map(|iid| g.level_entities.get(iid).copied())
+                .collect::<Vec<_>>()
+        });
+        for ent in removed {
+            eng.message(ent, MSG_REMOVE, Box::new(()));
+        }
+        Ok(())
+    }
+
+    fn finish_level(&self, level: &str, mut run: GhostRun, best: Option<f32>) {
+        let beaten = !G.with_borrow(|g| g.replaying)
+            && !self.resumed
+            && best.map_or(true, |best| self.level_timer < best);
+        if !beaten {
+            return;
+        }
+        run.time = self.level_timer;
+        let slot = SAVE.with_borrow(|save| save.slot);
+        save_ghost(slot, level, &run);
+    }
+
+    fn split(&mut self, eng: &mut Engine, level: &str) {
+        let segment = self.timer - self.split_start;
+        self.split_start = self.timer;
+        self.splits.push(level.to_string(), segment);
+
+        let segment_delta = self
+            .pb
+            .as_ref()
+            .and_then(|pb| pb.segment(level))
+            .map(|best| segment - best);
+        let run_delta = self.pb.as_ref().and_then(|pb| self.splits.delta(pb));
+        let mut content = match segment_delta {
+            Some(delta) => format!("{level} {delta:+.2}"),
+            None => format!("{level} {}", format_time(segment)),
+        };
+        // the run so far against the same levels of the personal best
+        if let Some(delta) = run_delta {
+            content.push_str(&format!("  run {delta:+.2}"));
+        }
+        let color = match run_delta.or(segment_delta) {
+            Some(delta) if delta < 0.0 => HIGHLIGHT,
+            Some(_) => RED,
+            None => GRAY,
+        };
+        if let Some(font) = FONT.with_borrow_mut(|font| font.fetch(eng)) {
+            self.split_text
+                .replace(text_sprite(eng, font, content, 20.0, color));
+        }
+    }
+
+    fn load_pending_level(&mut self, eng: &mut Engine) {
+        let Some(level) = G.with_borrow_mut(|g| g.loading_level.take()) else {
+            return;
+        };
+        let current_level = G.with_borrow(|g| g.current_level.clone());
+        let finished = level != current_level;
+        // entering a level replaces the run and the ghost of the previous one
+        let run = std::mem::take(&mut self.ghost_run);
+        let best = G.with_borrow(|g| g.ghost.as_ref().map(|ghost| ghost.time));
+        let respawn = if finished {
+            G.with_borrow_mut(|g| g.respawn.take())
+        } else {
+            None
+        };
+
+        match self.enter_level(eng, &level) {
+            Ok(_) => {
+                if finished {
+                    self.finish_level(&current_level, run, best);
+                    self.split(eng, &current_level);
+                    SAVE.with_borrow_mut(|save| {
+                        save.record_completion(&current_level, Some(&level), self.level_timer)
+                    });
+                }
+                // the level time keeps running from a checkpoint
+                if G.with_borrow(|g| g.respawn.is_some()) {
+                    self.resumed = true;
+                } else {
+                    self.level_timer = 0.0;
+                    self.resumed = false;
+                }
+                self.finish_recording();
+                self.start_recording(&level);
+                G.with_borrow_mut(|g| {
+                    g.current_level = level;
+                    let mut air = AirTank::empty(g.air_capacity());
+                    if let Some(respawn) = g.respawn.as_ref() {
+                        air.amount = respawn.air.min(air.capacity);
+                    }
+                    g.air = air;
+                })
+            }
+            Err(err) => {
+                // the failed load leaves the current level running
+                self.ghost_run = run;
+                if finished {
+                    G.with_borrow_mut(|g| g.respawn = respawn);
+                }
+                eprintln!("Can't load level {level} err {err:?}");
+            }
+        }
+    }
+
+    fn update_pause(&mut self, eng: &mut Engine) -> bool {
+        let Some(pause) = self.pause.as_mut() else {
+            return false;
+        };
+        match pause.update(eng) {
+            Some(PauseItem::Resume) => {
+                self.pause = None;
+                // don't let the key that closed the menu act this tick
+                return true;
+            }
+            Some(PauseItem::RestartLevel) => {
+                self.pause = None;
+                // restarting from the menu skips the checkpoints
+                G.with_borrow_mut(|g| {
+                    g.respawn = None;
+                    g.loading_level = Some(g.current_level.clone());
+                });
+                self.load_pending_level(eng);
+            }
+            Some(PauseItem::LevelSelect) => {
+                self.finish_recording();
+                eng.set_scene(LevelSelect::default());
+            }
+            Some(PauseItem::Quit) => {
+                self.finish_recording();
+                quit();
+            }
+            Some(PauseItem::Settings) | None => {}
+        }
+        true
+    }
+
+    /// Pick the controls of this tick, a replay also dictates the tick so it plays back exactly
+    fn next_controls(&mut self, eng: &mut Engine) -> Option<Controls> {
+        if let Some(playback) = self.playback.as_mut() {
+            let (controls, tick) = playback.next_frame()?;
+            eng.tick = tick;
+            return Some(controls);
         }
+        let controls = read_controls(eng);
+        if let Some(recording) = self.recording.as_mut() {
+            eng.tick = recording.push(&controls, eng.tick);
+        }
+        Some(controls)
     }
 }
 
 impl Scene for Demo {
     fn init(&mut self, eng: &mut Engine) {
-        // bind keys
-        let input = eng.input_mut();
-        input.bind(KeyCode::Left, Action::Left);
-        input.bind(KeyCode::Right, Action::Right);
-        input.bind(KeyCode::KeyA, Action::Left);
-        input.bind(KeyCode::KeyD, Action::Right);
-        input.bind(KeyCode::Up, Action::Up);
-        input.bind(KeyCode::KeyW, Action::Up);
-        input.bind(KeyCode::Down, Action::Down);
-        input.bind(KeyCode::KeyS, Action::Down);
-        input.bind(KeyCode::Space, Action::Jump);
-        input.bind(KeyCode::KeyI, Action::Inflate);
-        input.bind(KeyCode::KeyO, Action::Deflate);
-        input.bind(KeyCode::KeyR, Action::Restart);
-
-        eng.gravity = 400.0;
-        let level = G.with_borrow(|g| g.current_level);
-        PROJ.with_borrow(|proj| {
-            let level = format!("Level_{}", level);
-            eng.load_level(proj, &level).unwrap();
-            log::info!("Here we go.... {level}");
+        bind_keys(eng.input_mut());
+
+        let level = G.with_borrow_mut(|g| {
+            g.replaying = self.playback.is_some();
+            g.current_level.clone()
         });
+        if self.playback.is_none() {
+            let upgrades = SAVE.with_borrow(|save| save.progress.upgrades.clone());
+            G.with_borrow_mut(|g| g.upgrades = upgrades);
+            self.start_recording(&level);
+        }
+        self.pb = load_pb(SAVE.with_borrow(|save| save.slot));
+        // a saved or replayed level may be gone from the project
+        if let Err(err) = self.enter_level(eng, &level) {
+            eprintln!("Can't load level {level} err {err:?}");
+            G.with_borrow_mut(|g| g.replaying = false);
+            eng.set_scene(LevelSelect::default());
+            return;
+        }
+        log::info!("Here we go.... {level}");
     }
 
     fn update(&mut self, eng: &mut Engine) {
+        update_touch(eng);
+        S.with_borrow_mut(|sound| sound.update(eng));
+        // the world is frozen while paused, entities keep their state
+        if self.update_pause(eng) {
+            return;
+        }
+        // a replay covers a single attempt, leave when it ends or on Confirm
+        let watching = self.playback.is_some();
+        if !watching && read_controls(eng).just_pressed(Action::Pause) {
+            S.with_borrow_mut(|sound| sound.stop_playing(0.1));
+            self.pause = Some(Pause::new());
+            return;
+        }
+        let controls = match self.next_controls(eng) {
+            Some(controls) if !(watching && read_controls(eng).just_pressed(Action::Confirm)) => {
+                controls
+            }
+            _ => {
+                G.with_borrow_mut(|g| g.replaying = false);
+                eng.set_scene(LevelSelect::default());
+                return;
+            }
+        };
+        G.with_borrow_mut(|g| g.controls = controls);
+
+        if TUNING
+            .with_borrow_mut(|tuning| tuning.update(eng))
+            .is_some()
+        {
+            log::info!("Reloaded {TUNING_PATH}");
+            let level = G.with_borrow(|g| g.current_level.clone());
+            apply_tuning(eng, self.tuning(&level));
+        }
+
+        // restarting before the first split resets the run
+        if !watching && controls.just_pressed(Action::Restart) && self.splits.is_empty() {
+            self.frames = 0.0;
+            self.timer = 0.0;
+            self.split_start = 0.0;
+        }
+
         eng.scene_base_update();
+        for event in EVENTS.with_borrow_mut(|events| events.drain()) {
+            S.with_borrow_mut(|sound| sound.on_event(eng, event));
+        }
+        G.with_borrow_mut(|g| {
+            if let Some(cause) = g.death.update(eng.tick) {
+                log::info!("Reload {} after {}", g.current_level, cause.label());
+                g.loading_level = Some(g.current_level.clone());
+            }
+        });
         self.frames += 1.0;
         self.timer += eng.tick;
+        self.level_timer += eng.tick;
+        if let Some(body) = G.with_borrow(|g| g.player) {
+            self.ghost_run.record(self.level_timer, body.pos, body.size);
+        }
+
+        if eng.input().just_pressed(Action::ToggleGhost) {
+            SAVE.with_borrow_mut(|save| {
+                save.progress.hide_ghost = !save.progress.hide_ghost;
+                save.save();
+            });
+        }
 
         // render text
         FONT.with_borrow_mut(|font| {
             if let Some(font) = font.fetch(eng) {
-                self.remained_air_text.replace({
-                    let percent =
-                        ((G.with_borrow(|g| g.remained_air) * 100.0) as usize).clamp(0, 100);
-                    let content = format!("{percent}%");
-                    let text = Text::new(content, font.clone(), 28.0, Color::rgb(0x42, 0xbf, 0xe8));
-                    let (texture, size) = eng.create_text_texture(text);
-                    Sprite::new(texture, size)
+                self.air_text.replace({
+                    // in percent of a default tank, upgrades go over 100
+                    let air = G.with_borrow(|g| g.air);
+                    let content = format!(
+                        "{}/{}",
+                        (air.amount * 100.0).round(),
+                        (air.capacity * 100.0).round()
+                    );
+                    text_sprite(eng, font.clone(), content, 28.0, HIGHLIGHT)
                 });
                 self.dead_text.replace({
                     let content = format!("{}", G.with_borrow(|g| g.dead));
-                    let text = Text::new(content, font, 28.0, GRAY);
-                    let (texture, size) = eng.create_text_texture(text);
-                    Sprite::new(texture, size)
+                    text_sprite(eng, font.clone(), content, 28.0, GRAY)
+                });
+                self.timer_text.replace({
+                    let content = format_time(self.timer);
+                    text_sprite(eng, font, content, 28.0, WHITE)
                 });
             }
         });
 
-        if let Some(level) = G.with_borrow_mut(|g| g.loading_level.take()) {
-            let level_identifier = format!("Level_{}", level);
-            let res = PROJ.with_borrow(|proj| eng.load_level(proj, &level_identifier));
-            match res {
-                Ok(_) => G.with_borrow_mut(|g| {
-                    g.current_level = level;
-                    g.remained_air = 0.0;
-                }),
-                Err(err) => {
-                    eprintln!("Can't load level {level} err {err:?}");
-                }
+        if watching && G.with_borrow(|g| g.completed || g.loading_level.is_some()) {
+            G.with_borrow_mut(|g| g.replaying = false);
+            eng.set_scene(LevelSelect::default());
+            return;
+        }
+
+        if G.with_borrow(|g| g.completed) {
+            log::info!("Campaign complete");
+            self.finish_recording();
+            let (level, deaths) = G.with_borrow(|g| (g.current_level.clone(), g.dead));
+            let run = std::mem::take(&mut self.ghost_run);
+            let best = G.with_borrow(|g| g.ghost.as_ref().map(|ghost| ghost.time));
+            self.finish_level(&level, run, best);
+            self.split(eng, &level);
+            let pb = self.pb.take();
+            if self.splits.beats(pb.as_ref(), &level_identifier(0)) {
+                log::info!("New personal best {}", format_time(self.splits.total()));
+                save_pb(SAVE.with_borrow(|save| save.slot), &self.splits);
             }
+            SAVE.with_borrow_mut(|save| save.record_completion(&level, None, self.level_timer));
+            let splits = std::mem::take(&mut self.splits);
+            eng.set_scene(Results::new(deaths, self.timer, splits, pb));
+            return;
         }
+
+        self.load_pending_level(eng);
     }
 
     fn draw(&mut self, eng: &mut Engine) {
@@ -616,7 +2949,7 @@ impl Scene for Demo {
             );
             y_offset += text.sizef().y;
         }
-        if let Some(text) = self.remained_air_text.as_ref() {
+        if let Some(text) = self.air_text.as_ref() {
             let texture = load_texture(eng, "air-pump.png");
             let air_pump = Sprite::new(texture, UVec2::splat(32));
             eng.draw_image(
@@ -633,130 +2966,353 @@ impl Scene for Demo {
                 None,
             );
         }
+
+        // speedrun timer and the last split in the top right corner
+        let right = eng.view_size().x - 8.0;
+        let mut y = 0.0;
+        for text in [self.timer_text.as_ref(), self.split_text.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            let size = text.sizef();
+            eng.draw_image(
+                text,
+                Vec2::new(right - size.x * 0.5, y + size.y * 0.5),
+                None,
+                None,
+            );
+            y += size.y;
+        }
+
+        if self.playback.is_none() {
+            TOUCH.with_borrow(|touch| touch.draw(eng));
+        }
+        if let Some(pause) = self.pause.as_ref() {
+            pause.draw(eng);
+        }
     }
 }
 
-#[derive(Debug)]
-pub enum SoundType {
-    Jump,
-    Inflate,
-    Death,
+/// Native builds stream music from memory, the web can't spawn the decoder thread and decodes it upfront
+#[cfg(not(target_arch = "wasm32"))]
+type MusicHandle = kira::sound::streaming::StreamingSoundHandle<kira::sound::FromFileError>;
+#[cfg(target_arch = "wasm32")]
+type MusicHandle = StaticSoundHandle;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn music_data(
+    raw: Vec<u8>,
+) -> Result<kira::sound::streaming::StreamingSoundData<kira::sound::FromFileError>> {
+    Ok(kira::sound::streaming::StreamingSoundData::from_cursor(
+        Cursor::new(raw),
+    )?)
 }
 
+#[cfg(target_arch = "wasm32")]
+fn music_data(raw: Vec<u8>) -> Result<StaticSoundData> {
+    Ok(StaticSoundData::from_cursor(Cursor::new(raw))?)
+}
+
+#[derive(Default)]
 pub struct SoundManager {
-    audio: AudioManager<DefaultBackend>,
+    /// Created on the first sound once the platform allows it, browsers wait for a user gesture
+    audio: Option<AudioManager<DefaultBackend>>,
+    /// The audio device couldn't be opened, the game stays silent until `retry_audio`
+    unavailable: bool,
+    /// Playback errors are logged once, they repeat on every sound
+    play_failed: bool,
+    settings: AudioSettings,
+    sfx_track: Option<TrackHandle>,
+    music_track: Option<TrackHandle>,
+    music: Option<(String, Handle)>,
+    playing_music: Option<(String, MusicHandle)>,
+    ducked: bool,
+    banks_handle: Option<Handle>,
+    banks: HashMap<String, SoundBank>,
+    files: HashMap<String, Handle>,
     sounds_data: HashMap<Handle, StaticSoundData>,
-    jumps: Vec<Handle>,
-    inflate: Option<Handle>,
-    death: Option<Handle>,
     playing: Option<StaticSoundHandle>,
 }
 
-impl Default for SoundManager {
-    fn default() -> Self {
-        let audio = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()).unwrap();
-        Self {
-            audio,
-            sounds_data: Default::default(),
-            jumps: Default::default(),
-            inflate: None,
-            death: None,
-            playing: None,
+impl SoundManager {
+    fn audio(&mut self) -> Option<&mut AudioManager<DefaultBackend>> {
+        if self.audio.is_none() && !self.unavailable && audio_unlocked() {
+            match AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()) {
+                Ok(mut audio) => {
+                    self.sfx_track = Self::add_track(&mut audio);
+                    self.music_track = Self::add_track(&mut audio);
+                    self.audio = Some(audio);
+                    self.apply_volumes();
+                }
+                Err(err) => {
+                    log::error!("Can't open the audio device, sounds are off err {err:?}");
+                    self.unavailable = true;
+                }
+            }
         }
+        self.audio.as_mut()
     }
-}
 
-impl SoundManager {
-    fn load(&mut self, eng: &mut Engine) {
-        self.jumps = (1..=8)
-            .map(|i| {
-                eng.assets
-                    .load_bytes(format!("sounds/arrowHit/arrowHit0{i}.wav"))
-            })
-            .collect();
-        self.inflate
-            .replace(eng.assets.load_bytes("sounds/48_Speed_up_02.wav"));
-        self.death
-            .replace(eng.assets.load_bytes("sounds/21_Debuff_01.wav"));
+    fn add_track(audio: &mut AudioManager<DefaultBackend>) -> Option<TrackHandle> {
+        match audio.add_sub_track(TrackBuilder::new()) {
+            Ok(track) => Some(track),
+            Err(err) => {
+                log::error!("Can't add audio track err {err:?}");
+                None
+            }
+        }
+    }
+
+    fn audio_settings(&self) -> AudioSettings {
+        self.settings
+    }
+
+    fn set_audio_settings(&mut self, settings: AudioSettings) {
+        self.settings = settings;
+        settings.save();
+        self.apply_volumes();
+    }
+
+    fn apply_volumes(&mut self) {
+        let settings = self.settings;
+        if let Some(audio) = self.audio.as_mut() {
+            audio
+                .main_track()
+                .set_volume(settings.master_volume() as f64, Tween::default());
+        }
+        if let Some(track) = self.sfx_track.as_mut() {
+            track.set_volume(settings.volume(Bus::Sfx) as f64, Tween::default());
+        }
+        self.apply_music_volume(Tween::default());
+    }
+
+    fn apply_music_volume(&mut self, tween: Tween) {
+        let mut volume = self.settings.volume(Bus::Music);
+        if self.ducked {
+            volume *= MUSIC_DUCKING;
+        }
+        if let Some(track) = self.music_track.as_mut() {
+            track.set_volume(volume as f64, tween);
+        }
+    }
+
+    /// Crossfade to the music at `path`, `None` fades out to silence
+    fn play_music(&mut self, eng: &mut Engine, path: Option<String>) {
+        if self.music.as_ref().map(|(p, _)| p) == path.as_ref() {
+            return;
+        }
+        self.music = path.map(|path| {
+            let handle = eng.assets.load_bytes(&path);
+            (path, handle)
+        });
+    }
+
+    fn load_banks(&mut self, eng: &mut Engine) {
+        let Some(handle) = self.banks_handle.as_ref() else {
+            return;
+        };
+        let Some(raw) = eng.assets.get_raw(handle) else {
+            return;
+        };
+        match parse_sound_banks(raw) {
+            Ok(banks) => self.banks = banks,
+            Err(err) => log::error!("Can't read {SOUNDS_PATH} err {err:?}"),
+        }
+        self.banks_handle = None;
+        for path in self.banks.values().flat_map(|bank| &bank.files) {
+            if !self.files.contains_key(path) {
+                let handle = eng.assets.load_bytes(path);
+                self.files.insert(path.clone(), handle);
+            }
+        }
     }
 
-    fn fetch(&mut self, eng: &Engine, sound: SoundType) -> Option<StaticSoundData> {
-        let handle = match sound {
-            SoundType::Jump => {
-                let mut rng = thread_rng();
-                self.jumps.choose(&mut rng)?
+    /// Start the music once loaded and duck it under the inflate/deflate loop, call it every frame
+    fn update(&mut self, eng: &mut Engine) {
+        self.load_banks(eng);
+        let ducked = self
+            .playing
+            .as_ref()
+            .is_some_and(|s| s.state() == PlaybackState::Playing);
+        if ducked != self.ducked {
+            self.ducked = ducked;
+            self.apply_music_volume(Tween {
+                duration: Duration::from_secs_f32(0.3),
+                ..Default::default()
+            });
+        }
+
+        let wanted = self.music.as_ref().map(|(path, _)| path);
+        if self.playing_music.as_ref().map(|(path, _)| path) == wanted {
+            return;
+        }
+        let fade = Tween {
+            duration: Duration::from_secs_f32(MUSIC_FADE),
+            ..Default::default()
+        };
+        let Some((path, handle)) = self.music.as_ref() else {
+            if let Some((_, mut music)) = self.playing_music.take() {
+                music.stop(fade);
+            }
+            return;
+        };
+        let Some(raw) = eng.assets.get_raw(handle).cloned() else {
+            return;
+        };
+        let path = path.clone();
+        if self.audio().is_none() {
+            return;
+        }
+        let data = match music_data(raw) {
+            Ok(data) => data.loop_region(..).fade_in_tween(fade),
+            Err(err) => {
+                log::error!("Can't decode music {path} err {err:?}");
+                self.music = None;
+                return;
+            }
+        };
+        let data = match self.music_track.as_ref() {
+            Some(track) => data.output_destination(track),
+            None => data,
+        };
+        let Some(audio) = self.audio.as_mut() else {
+            return;
+        };
+        match audio.play(data) {
+            Ok(music) => {
+                if let Some((_, mut previous)) = self.playing_music.replace((path, music)) {
+                    previous.stop(fade);
+                }
+            }
+            Err(err) => {
+                log::error!("Can't play music {path} err {err:?}");
+                self.music = None;
             }
-            SoundType::Inflate => self.inflate.as_ref()?,
-            SoundType::Death => self.death.as_ref()?,
+        }
+    }
+
+    fn play(&mut self, data: StaticSoundData) -> Option<StaticSoundHandle> {
+        self.audio()?;
+        let data = match self.sfx_track.as_ref() {
+            Some(track) => data.output_destination(track),
+            None => data,
         };
+        match self.audio.as_mut()?.play(data) {
+            Ok(handle) => Some(handle),
+            Err(err) => {
+                if !self.play_failed {
+                    log::error!("Can't play sound err {err:?}");
+                    self.play_failed = true;
+                }
+                None
+            }
+        }
+    }
+
+    fn is_unavailable(&self) -> bool {
+        self.unavailable
+    }
+
+    fn retry_audio(&mut self) -> bool {
+        self.unavailable = false;
+        self.play_failed = false;
+        self.audio().is_some()
+    }
+
+    fn load(&mut self, eng: &mut Engine) {
+        self.banks_handle
+            .replace(eng.assets.load_bytes(SOUNDS_PATH));
+        self.settings = AudioSettings::load();
+        // open the device early where no user gesture is needed
+        self.audio();
+    }
+
+    fn fetch(&mut self, eng: &Engine, path: &str) -> Option<StaticSoundData> {
+        let handle = self.files.get(path)?;
         match self.sounds_data.get(handle) {
             Some(data) => {
-                log::debug!("Get sound {sound:?} cached");
+                log::debug!("Get sound {path} cached");
                 Some(data.to_owned())
             }
             None => {
                 let Some(raw) = eng.assets.get_raw(handle).cloned() else {
-                    log::debug!("Get sound {sound:?} not ready");
+                    log::debug!("Get sound {path} not ready");
                     return None;
                 };
-                log::debug!("Get sound {sound:?} done");
-                let data = StaticSoundData::from_media_source(Cursor::new(raw)).unwrap();
-                self.sounds_data.insert(handle.to_owned(), data.clone());
-                Some(data)
+                log::debug!("Get sound {path} done");
+                match StaticSoundData::from_media_source(Cursor::new(raw)) {
+                    Ok(data) => {
+                        self.sounds_data.insert(handle.to_owned(), data.clone());
+                        Some(data)
+                    }
+                    Err(err) => {
+                        log::error!("Can't decode sound {path} err {err:?}");
+                        None
+                    }
+                }
             }
         }
     }
 
-    fn play_collide(&mut self, eng: &Engine) {
-        let Some(s) = self.fetch(eng, SoundType::Jump) else {
-            return;
-        };
-        let mut s = self.audio.play(s).unwrap();
-        s.set_volume(0.3, Default::default());
-        let mut rng = thread_rng();
-        let rate = rng.gen_range(2.8..3.4);
-        s.set_playback_rate(rate, Tween::default());
+    fn play_event(&mut self, eng: &Engine, event: GameEvent) -> Option<StaticSoundHandle> {
+        let bank = self.banks.get(event.name())?;
+        let (path, rate) = RNG.with_borrow_mut(|rng| {
+            let path = bank.files.choose(rng)?.clone();
+            let [min, max] = bank.rate;
+            let rate = if min < max {
+                rng.gen_range(min..max)
+            } else {
+                min
+            };
+            Some((path, rate))
+        })?;
+        let (mut volume, mut rate, mut panning) = (bank.volume, rate, 0.5);
+        if let GameEvent::Bounced(impact) = event {
+            volume *= impact_volume(impact.speed);
+            rate *= impact_rate(impact.size);
+            panning = impact.panning;
+        }
+        let loop_region = bank.loop_region;
+        let mut data = self
+            .fetch(eng, &path)?
+            .volume(volume as f64)
+            .playback_rate(rate as f64)
+            .panning(panning as f64);
+        if let Some([start, end]) = loop_region {
+            data = data.loop_region(start..end);
+        }
+        self.play(data)
     }
 
-    fn play_inflate(&mut self, eng: &Engine) {
-        if self
-            .playing
-            .as_ref()
-            .is_some_and(|s| s.state() == PlaybackState::Playing)
-        {
-            return;
-        };
-        if let Some(s) = self.fetch(eng, SoundType::Inflate) {
-            let mut s = self.audio.play(s).unwrap();
-            s.set_loop_region(0.0..1.0);
-            s.set_volume(0.5, Default::default());
-            s.set_playback_rate(2.4, Tween::default());
-            self.playing.replace(s);
+    fn stop_playing(&mut self, fade: f32) {
+        if let Some(mut s) = self.playing.take() {
+            s.stop(Tween {
+                duration: Duration::from_secs_f32(fade),
+                ..Default::default()
+            })
         }
     }
+}
 
-    fn play_deflate(&mut self, eng: &Engine) {
-        if self
+impl EventListener for SoundManager {
+    fn on_event(&mut self, eng: &Engine, event: GameEvent) {
+        let playing = self
             .playing
             .as_ref()
-            .map(|s| s.state() == PlaybackState::Playing && s.position() < 2.0)
-            .unwrap_or_default()
-        {
-            return;
-        };
-        let Some(s) = self.fetch(eng, SoundType::Inflate).clone() else {
-            return;
-        };
-        let mut s = self.audio.play(s).unwrap();
-        s.set_volume(0.5, Default::default());
-        s.set_playback_rate(3.8, Tween::default());
-        self.playing.replace(s);
-    }
-
-    fn play_killed(&mut self, eng: &Engine) {
-        if let Some(s) = self.fetch(eng, SoundType::Death) {
-            let mut sound = self.audio.play(s).unwrap();
-            sound.set_playback_rate(2., Tween::default());
+            .filter(|s| s.state() == PlaybackState::Playing);
+        match event {
+            // the inflate loop keeps going, deflating restarts its sound every 2 seconds
+            GameEvent::Inflating if playing.is_some() => {}
+            GameEvent::Deflating if playing.is_some_and(|s| s.position() < 2.0) => {}
+            GameEvent::Inflating | GameEvent::Deflating => {
+                if let Some(s) = self.play_event(eng, event) {
+                    self.playing.replace(s);
+                }
+            }
+            GameEvent::AirStopped => self.stop_playing(0.5),
+            _ => {
+                self.play_event(eng, event);
+            }
         }
     }
 }
@@ -786,6 +3342,81 @@ impl FontManager {
     }
 }
 
+/// Keeps `tuning.json` loaded, the file is read again periodically so edits apply while playing
+///
+/// The defaults stay in use while the file can't be read. The web build reads it once, every
+/// read there is a network fetch.
+#[derive(Default)]
+pub struct TuningManager {
+    handle: Option<Handle>,
+    waited: u32,
+    tuning: Tuning,
+    loaded: bool,
+    reload_timer: f32,
+}
+
+impl TuningManager {
+    fn load(&mut self, eng: &mut Engine) {
+        self.handle.replace(eng.assets.load_bytes(TUNING_PATH));
+        self.waited = 0;
+    }
+
+    fn update(&mut self, eng: &mut Engine) -> Option<Tuning> {
+        let Some(handle) = self.handle.as_ref() else {
+            if cfg!(target_arch = "wasm32") {
+                return None;
+            }
+            self.reload_timer += eng.tick;
+            if self.reload_timer >= TUNING_RELOAD_INTERVAL {
+                self.reload_timer = 0.0;
+                self.load(eng);
+            }
+            return None;
+        };
+        let Some(data) = eng.assets.get_raw(handle) else {
+            // the engine drops failed reads without a word
+            self.waited += 1;
+            if self.waited > TUNING_READ_FRAMES {
+                if !self.loaded {
+                    log::error!("Can't read {TUNING_PATH}, playing with the default tuning");
+                }
+                self.handle = None;
+                self.loaded = true;
+            }
+            return None;
+        };
+        let res = Tuning::from_slice(data);
+        self.handle = None;
+        self.loaded = true;
+        match res {
+            Ok(tuning) if tuning != self.tuning => {
+                self.tuning = tuning;
+                Some(tuning)
+            }
+            Ok(_) => None,
+            Err(err) => {
+                log::error!("Invalid {TUNING_PATH} err {err:?}");
+                None
+            }
+        }
+    }
+}
+
+/// Tuning of `assets/tuning.json` with the overrides of `level`
+fn level_tuning(level: &str) -> Tuning {
+    let base = TUNING.with_borrow(|tuning| tuning.tuning);
+    PROJ.with_borrow(|proj| {
+        proj.get_level(level)
+            .map(|level| base.for_level(level))
+            .unwrap_or(base)
+    })
+}
+
+fn apply_tuning(eng: &mut Engine, tuning: Tuning) {
+    eng.gravity = tuning.gravity;
+    G.with_borrow_mut(|g| g.tuning = tuning);
+}
+
 pub fn app() -> App {
     App::default()
         .title("Balloon Game".to_string())
@@ -794,10 +3425,23 @@ pub fn app() -> App {
 }
 
 pub fn setup(eng: &mut Engine) {
+    setup_with(eng, None);
+}
+
+pub fn setup_replay(eng: &mut Engine, replay: Replay) {
+    setup_with(eng, Some(replay));
+}
+
+fn setup_with(eng: &mut Engine, replay: Option<Replay>) {
     // Setup game state
+    let level = SAVE.with_borrow_mut(|save| {
+        save.load(last_slot());
+        save.progress.last_level.clone()
+    });
     G.with_borrow_mut(|g| {
         g.dead = 0;
-        g.current_level = 0;
+        g.deaths = DeathTally::default();
+        g.start_level(level.unwrap_or_else(|| level_identifier(0)));
     });
 
     // Load LDTK project
@@ -812,6 +3456,10 @@ pub fn setup(eng: &mut Engine) {
         font.load(eng);
     });
 
+    TUNING.with_borrow_mut(|tuning| {
+        tuning.load(eng);
+    });
+
     // set resize and scale
     eng.set_view_size(VIEW_SIZE);
     eng.set_scale_mode(ScaleMode::Exact);
@@ -826,5 +3474,12 @@ pub fn setup(eng: &mut Engine) {
     eng.add_entity_type::<Button>();
     eng.add_entity_type::<Inflator>();
     eng.add_entity_type::<Crown>();
-    eng.set_scene(Loading { handle });
+    eng.add_entity_type::<Ghost>();
+    eng.add_entity_type::<Fan>();
+    eng.add_entity_type::<MovingPlatform>();
+    eng.add_entity_type::<Gate>();
+    eng.add_entity_type::<Checkpoint>();
+    eng.add_entity_type::<DeathEffect>();
+    eng.add_entity_type::<Shred>();
+    eng.set_scene(Loading { handle, replay });
 }
@@ -1,5 +1,23 @@
-use balloon_game::{app, setup};
+use std::fs;
+
+use balloon_game::{app, replay::Replay, setup, setup_replay};
 
 pub fn main() {
-    app().run_block(setup).unwrap();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [flag, path] if flag == "--replay" => {
+            let replay = match fs::read_to_string(path)
+                .map_err(Into::into)
+                .and_then(|data| Replay::from_json(&data))
+            {
+                Ok(replay) => replay,
+                Err(err) => {
+                    eprintln!("Can't read replay {path}: {err}");
+                    std::process::exit(1);
+                }
+            };
+            app().run_block(|eng| setup_replay(eng, replay)).unwrap();
+        }
+        _ => app().run_block(setup).unwrap(),
+    }
 }
@@ -0,0 +1,65 @@
+use std::{fs, path::PathBuf};
+
+use roast_2d::prelude::{Result, Vec2};
+
+const STORAGE_DIR: &str = "saves";
+
+fn storage_path(key: &str) -> PathBuf {
+    PathBuf::from(STORAGE_DIR).join(format!("{key}.json"))
+}
+
+pub fn read_storage(key: &str) -> Option<String> {
+    fs::read_to_string(storage_path(key)).ok()
+}
+
+pub fn write_storage(key: &str, data: &str) -> Result<()> {
+    fs::create_dir_all(STORAGE_DIR)?;
+    // write to a temporary file first, so a crash never leaves a truncated save behind
+    let path = storage_path(key);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+/// Files exported for the player sit next to the saves
+pub fn export_file(key: &str, data: &str) -> Result<()> {
+    write_storage(key, data)
+}
+
+pub fn quit() {
+    std::process::exit(0);
+}
+
+/// Drawable size of the game window, roast-2d doesn't expose it
+pub fn window_size() -> Option<Vec2> {
+    let (mut width, mut height) = (0, 0);
+    // SAFETY: SDL returns null when no window has the GL context or the keyboard focus, the
+    // window is only read while the engine owns it
+    unsafe {
+        // the engine renders to an OpenGL window, other renderers leave no context current
+        let mut window = sdl2_sys::SDL_GL_GetCurrentWindow();
+        if window.is_null() {
+            window = sdl2_sys::SDL_GetKeyboardFocus();
+        }
+        if window.is_null() {
+            return None;
+        }
+        sdl2_sys::SDL_GL_GetDrawableSize(window, &mut width, &mut height);
+    }
+    (width > 0 && height > 0).then(|| Vec2::new(width as f32, height as f32))
+}
+
+/// Touch points in view coordinates, the desktop build only reads the keyboard and gamepads
+pub fn touches() -> Vec<Vec2> {
+    Vec::new()
+}
+
+pub fn is_touch_device() -> bool {
+    false
+}
+
+/// Desktop audio can start right away
+pub fn audio_unlocked() -> bool {
+    true
+}
@@ -0,0 +1,109 @@
+use roast_2d::prelude::*;
+use serde_json::Value;
+
+use crate::fields;
+
+const DEFAULT_SPEED: f32 = 60.0;
+const DEFAULT_PAUSE: f32 = 0.5;
+
+/// How a platform goes on after its last waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    /// Walk the waypoints back to the first one
+    PingPong,
+    /// Go straight back to the first waypoint
+    Loop,
+}
+
+/// Waypoints of a `MovingPlatform`, the first one is where the platform is placed
+#[derive(Debug, Clone)]
+pub struct PlatformPath {
+    points: Vec<Vec2>,
+    mode: PathMode,
+    /// Pixels per second
+    speed: f32,
+    /// Seconds waited on each waypoint
+    pause: f32,
+    target: usize,
+    forward: bool,
+    wait: f32,
+}
+
+impl PlatformPath {
+    pub fn new(points: Vec<Vec2>, mode: PathMode, speed: f32, pause: f32) -> Self {
+        Self {
+            target: 1.min(points.len().saturating_sub(1)),
+            points,
+            mode,
+            speed,
+            pause,
+            forward: true,
+            wait: 0.0,
+        }
+    }
+
+    /// Read the LDtk fields `Path` (Array of Points), `Mode` (PingPong or Loop), `Speed` and `Pause`
+    ///
+    /// Points are cells of `grid` pixels, the platform goes through their centers.
+    pub fn from_settings(pos: Vec2, settings: &Value, grid: f32) -> Self {
+        let mut points = vec![pos];
+        if let Some(path) = settings.get("Path").and_then(|v| v.as_array()) {
+            points.extend(path.iter().filter_map(|point| {
+                let cx = point.get("cx")?.as_f64()? as f32;
+                let cy = point.get("cy")?.as_f64()? as f32;
+                Some((Vec2::new(cx, cy) + 0.5) * grid)
+            }));
+        }
+        let mode = fields::variant(
+            settings,
+            "Mode",
+            &[("PingPong", PathMode::PingPong), ("Loop", PathMode::Loop)],
+            PathMode::PingPong,
+        );
+        let field = |name, default| fields::float(settings, name).unwrap_or(default);
+        Self::new(
+            points,
+            mode,
+            field("Speed", DEFAULT_SPEED),
+            field("Pause", DEFAULT_PAUSE),
+        )
+    }
+
+    /// Velocity of the platform at `pos` for the next `tick`, it lands exactly on the waypoints
+    pub fn velocity(&mut self, pos: Vec2, tick: f32) -> Vec2 {
+        if self.points.len() < 2 || tick <= 0.0 {
+            return Vec2::ZERO;
+        }
+        if self.wait > 0.0 {
+            self.wait -= tick;
+            return Vec2::ZERO;
+        }
+        let to_target = self.points[self.target] - pos;
+        let distance = to_target.length();
+        if distance <= self.speed * tick {
+            self.advance();
+            self.wait = self.pause;
+            return to_target / tick;
+        }
+        to_target / distance * self.speed
+    }
+
+    fn advance(&mut self) {
+        let last = self.points.len() - 1;
+        match self.mode {
+            PathMode::Loop => self.target = (self.target + 1) % self.points.len(),
+            PathMode::PingPong => {
+                if self.target == last {
+                    self.forward = false;
+                } else if self.target == 0 {
+                    self.forward = true;
+                }
+                self.target = if self.forward {
+                    self.target + 1
+                } else {
+                    self.target - 1
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use roast_2d::prelude::{anyhow::anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{checkpoint::Respawn, read_storage, tuning::Tuning, write_storage, Controls};
+
+pub const REPLAY_VERSION: u64 = 1;
+
+/// Ticks are stored in 1/10 ms, fine enough to be invisible and coarse enough to compress
+const TICK_RESOLUTION: f32 = 10_000.0;
+const LAST_REPLAY_KEY: &str = "replay_last";
+
+/// Consecutive frames sharing the same pressed actions and tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Run {
+    /// Bits of the pressed `Action`s
+    pressed: u16,
+    /// Strength of the analog actions, absent when fully pressed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strength: Option<[u8; 6]>,
+    tick: u32,
+    frames: u32,
+}
+
+/// Inputs of one attempt at a level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    version: u64,
+    pub level: String,
+    /// Seed of the game RNG when the attempt started
+    pub seed: u64,
+    /// The player entered the level through a door spawning it here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn_point: Option<[f32; 2]>,
+    /// The attempt went on from this checkpoint instead of the level start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<Respawn>,
+    /// Air capacity upgrades owned when the attempt started
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub upgrades: BTreeMap<String, f32>,
+    /// Tuning of the level when the attempt started, older replays play with the current one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tuning: Option<Tuning>,
+    runs: Vec<Run>,
+}
+
+impl Replay {
+    pub fn new(level: String, seed: u64) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            level,
+            seed,
+            spawn_point: None,
+            checkpoint: None,
+            upgrades: BTreeMap::new(),
+            tuning: None,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Record a frame, return the tick rounded like it will be replayed
+    pub fn push(&mut self, controls: &Controls, tick: f32) -> f32 {
+        let tick = (tick * TICK_RESOLUTION).round() as u32;
+        let pressed = controls.pressed_bits();
+        let strength = controls.strength_bytes();
+        match self.runs.last_mut() {
+            Some(run) if run.pressed == pressed && run.strength == strength && run.tick == tick => {
+                run.frames += 1
+            }
+            _ => self.runs.push(Run {
+                pressed,
+                strength,
+                tick,
+                frames: 1,
+            }),
+        }
+        tick as f32 / TICK_RESOLUTION
+    }
+
+    pub fn frames(&self) -> usize {
+        self.runs.iter().map(|run| run.frames as usize).sum()
+    }
+
+    /// Controls and tick of every frame
+    pub fn iter(&self) -> impl Iterator<Item = (Controls, f32)> + '_ {
+        let mut previous = Controls::default();
+        self.runs
+            .iter()
+            .flat_map(|run| (0..run.frames).map(move |_| run))
+            .map(move |run| {
+                let controls = Controls::from_parts(run.pressed, run.strength).after(&previous);
+                previous = controls;
+                (controls, run.tick as f32 / TICK_RESOLUTION)
+            })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(data: &str) -> Result<Self> {
+        let replay: Self = serde_json::from_str(data)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(anyhow!("Unsupported replay version {}", replay.version));
+        }
+        Ok(replay)
+    }
+}
+
+/// The most recent attempt, kept to reproduce bugs
+pub fn load_last() -> Option<Replay> {
+    let data = read_storage(LAST_REPLAY_KEY)?;
+    match Replay::from_json(&data) {
+        Ok(replay) => Some(replay),
+        Err(err) => {
+            log::error!("Can't read last replay err {err:?}");
+            None
+        }
+    }
+}
+
+pub fn save_last(replay: &Replay) {
+    if let Err(err) = replay
+        .to_json()
+        .and_then(|data| write_storage(LAST_REPLAY_KEY, &data))
+    {
+        log::error!("Can't write last replay err {err:?}");
+    }
+}
+
+/// Feeds a replay back one frame at a time
+pub struct Playback {
+    frames: Vec<(Controls, f32)>,
+    cursor: usize,
+    tuning: Option<Tuning>,
+}
+
+impl Playback {
+    pub fn new(replay: &Replay) -> Self {
+        Self {
+            frames: replay.iter().collect(),
+            cursor: 0,
+            tuning: replay.tuning,
+        }
+    }
+
+    pub fn tuning(&self) -> Option<Tuning> {
+        self.tuning
+    }
+
+    /// Controls and tick of the next frame, `None` once the replay is over
+    pub fn next_frame(&mut self) -> Option<(Controls, f32)> {
+        let frame = self.frames.get(self.cursor).copied();
+        self.cursor += 1;
+        frame
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use roast_2d::prelude::{anyhow::anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{read_storage, write_storage};
+
+/// Version of the save schema, bump it and append a migration when `Progress` changes
+pub const SAVE_VERSION: u64 = 2;
+pub const SAVE_SLOTS: usize = 3;
+const LAST_SLOT_KEY: &str = "last_slot";
+
+/// Upgrade steps of old saves, `MIGRATIONS[n]` converts a version `n + 1` save into version `n + 2`
+const MIGRATIONS: &[fn(&mut Value) -> Result<()>] = &[migrate_unlocked_levels];
+
+/// Version 1 only knew the linear campaign, unlock every level up to `highest_unlocked` by name
+fn migrate_unlocked_levels(save: &mut Value) -> Result<()> {
+    let progress = save
+        .get_mut("progress")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| anyhow!("Save has no progress"))?;
+    let highest_unlocked = progress
+        .remove("highest_unlocked")
+        .and_then(|v| v.as_u64())
+        .unwrap_or_default();
+    let unlocked: Vec<Value> = (0..=highest_unlocked)
+        .map(|level| format!("Level_{level}").into())
+        .collect();
+    progress.insert(
+        "last_level".to_string(),
+        format!("Level_{highest_unlocked}").into(),
+    );
+    progress.insert("unlocked".to_string(), unlocked.into());
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LevelRecord {
+    pub deaths: usize,
+    pub completed: bool,
+    pub best_time: Option<f32>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Progress {
+    /// The level to resume from
+    pub last_level: Option<String>,
+    pub unlocked: BTreeSet<String>,
+    pub levels: BTreeMap<String, LevelRecord>,
+    #[serde(default)]
+    pub hide_ghost: bool,
+    /// Air capacity raised by each upgrading `Inflator`, by LDtk iid
+    #[serde(default)]
+    pub upgrades: BTreeMap<String, f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u64,
+    progress: Progress,
+}
+
+fn slot_key(slot: usize) -> String {
+    format!("slot_{slot}")
+}
+
+fn backup_key(slot: usize) -> String {
+    format!("slot_{slot}_backup")
+}
+
+/// Parse a save of any supported version
+pub fn parse_save(data: &str) -> Result<Progress> {
+    migrate(serde_json::from_str(data)?)
+}
+
+fn migrate(mut value: Value) -> Result<Progress> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow!("Save has no version"))?;
+    if version == 0 || version > SAVE_VERSION {
+        return Err(anyhow!("Unsupported save version {version}"));
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        migration(&mut value)?;
+        log::info!("Migrated save from version {} to {}", from + 1, from + 2);
+    }
+    value["version"] = SAVE_VERSION.into();
+    let save: SaveFile = serde_json::from_value(value)?;
+    Ok(save.progress)
+}
+
+/// Read progress of a slot, return `None` if the slot is empty
+pub fn load_slot(slot: usize) -> Result<Option<Progress>> {
+    let Some(data) = read_storage(&slot_key(slot)) else {
+        return Ok(None);
+    };
+    parse_save(&data).map(Some)
+}
+
+/// Copy an unreadable slot aside before a new game overwrites it
+fn backup_slot(slot: usize) -> Result<()> {
+    let data = read_storage(&slot_key(slot)).ok_or_else(|| anyhow!("Slot {slot} is empty"))?;
+    write_storage(&backup_key(slot), &data)
+}
+
+/// The slot played last, the first one on a new install
+pub fn last_slot() -> usize {
+    read_storage(LAST_SLOT_KEY)
+        .and_then(|data| data.trim().parse().ok())
+        .filter(|slot| *slot < SAVE_SLOTS)
+        .unwrap_or_default()
+}
+
+pub fn save_slot(slot: usize, progress: &Progress) -> Result<()> {
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        progress: progress.clone(),
+    };
+    let data = serde_json::to_string(&save)?;
+    write_storage(&slot_key(slot), &data)
+}
+
+#[derive(Default)]
+pub struct SaveManager {
+    pub slot: usize,
+    pub progress: Progress,
+    /// The slot couldn't be loaded nor backed up, it is never overwritten
+    read_only: bool,
+}
+
+impl SaveManager {
+    /// Switch to a slot, an empty or unreadable slot starts a new game
+    ///
+    /// An unreadable slot is backed up first, or left untouched if that fails.
+    pub fn load(&mut self, slot: usize) {
+        self.slot = slot;
+        self.read_only = false;
+        self.progress = match load_slot(slot) {
+            Ok(progress) => progress.unwrap_or_default(),
+            Err(err) => {
+                log::error!("Can't load save slot {slot} err {err:?}");
+                if let Err(err) = backup_slot(slot) {
+                    log::error!("Can't back up save slot {slot}, it stays read-only err {err:?}");
+                    self.read_only = true;
+                }
+                Progress::default()
+            }
+        };
+        if let Err(err) = write_storage(LAST_SLOT_KEY, &slot.to_string()) {
+            log::error!("Can't remember save slot {slot} err {err:?}");
+        }
+    }
+
+    pub fn save(&self) {
+        if self.read_only {
+            return;
+        }
+        if let Err(err) = save_slot(self.slot, &self.progress) {
+            log::error!("Can't write save slot {} err {err:?}", self.slot);
+        }
+    }
+
+    pub fn record_death(&mut self, level: &str) {
+        self.progress
+            .levels
+            .entry(level.to_string())
+            .or_default()
+            .deaths += 1;
+        self.save();
+    }
+
+    pub fn record_upgrade(&mut self, inflator: &str, capacity: f32) {
+        self.progress
+            .upgrades
+            .insert(inflator.to_string(), capacity);
+        self.save();
+    }
+
+    pub fn record_completion(&mut self, level: &str, unlocked: Option<&str>, time: f32) {
+        let record = self.progress.levels.entry(level.to_string()).or_default();
+        record.completed = true;
+        record.best_time = Some(record.best_time.map_or(time, |best| best.min(time)));
+        if let Some(unlocked) = unlocked {
+            self.progress.unlocked.insert(unlocked.to_string());
+            self.progress.last_level = Some(unlocked.to_string());
+        }
+        self.save();
+    }
+}
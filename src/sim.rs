@@ -0,0 +1,549 @@
+//! Headless model of the balloon physics in a level.
+//!
+//! Steps `Balloon` at a fixed tick with scripted `Controls`. It is not the game: roast-2d's
+//! `Engine` can't run without its SDL or web platform, and its `trace` is crate private, so the
+//! world step is a port of roast-2d 0.0.5's `entity_base_update` and trace, and the game
+//! entities, scenes and sounds don't run. `Cargo.toml` pins that version, updating the engine
+//! means checking the port against it.
+//! The model only covers the collision layer and a few entities checked by their bounds:
+//! `Door` finishes the run, `Spikes` kill, `Inflator` refills the air and `Fan` winds push.
+//! Bursting, sharp tiles and leaving the level end the run like a death.
+//! Buttons, checkpoints, crowns, `MovingPlatform`s and `Gate`s are left out, their behavior isn't
+//! tested here.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use glam::{IVec2, UVec2};
+use roast_2d::{
+    ldtk::{LayerType, LdtkProject},
+    prelude::{anyhow::anyhow, *},
+};
+
+use crate::{
+    air::{AirTank, Pump, Refill},
+    checkpoint::Respawn,
+    death::out_of_bounds,
+    fan::Wind,
+    replay::Replay,
+    tuning::Tuning,
+    Balloon, Body, Controls, PLAYER_SIZE,
+};
+
+pub const SIM_TICK: f32 = 1.0 / 60.0;
+
+const ENTITY_MIN_BOUNCE_VELOCITY: f32 = 10.0;
+const MAX_GROUND_NORMAL: f32 = 0.69;
+const MIN_SLIDE_NORMAL: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimState {
+    Running,
+    Dead,
+    ReachedDoor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemKind {
+    Door,
+    Spikes,
+    Inflator(Pump),
+}
+
+struct Item {
+    /// LDtk iid
+    iid: String,
+    kind: ItemKind,
+    bounds: Rect,
+    alive: bool,
+}
+
+pub fn load_project<P: AsRef<Path>>(path: P) -> Result<LdtkProject> {
+    let data = fs::read(path)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub struct Sim {
+    map: Option<CollisionMap>,
+    balloon: Balloon,
+    body: Body,
+    air: AirTank,
+    /// Air capacity of a tank without upgrades
+    air_capacity: f32,
+    upgrades: BTreeMap<String, f32>,
+    gravity: f32,
+    items: Vec<Item>,
+    winds: Vec<Wind>,
+    previous: Controls,
+    state: SimState,
+    frame: usize,
+}
+
+impl Sim {
+    pub fn new(proj: &LdtkProject, level: &str) -> Result<Self> {
+        Self::with_tuning(proj, level, &Tuning::default())
+    }
+
+    /// Simulate with `tuning`, the overrides of the level still apply
+    pub fn with_tuning(proj: &LdtkProject, level: &str, tuning: &Tuning) -> Result<Self> {
+        let level = proj.get_level(level)?;
+        let tuning = tuning.for_level(level);
+        let mut map = None;
+        let mut spawn = None;
+        let mut items = Vec::new();
+        let mut winds = Vec::new();
+        for layer in &level.layer_instances {
+            match layer.r#type {
+                LayerType::IntGrid if layer.identifier == "Collision" => {
+                    map = Some(CollisionMap {
+                        name: layer.identifier.clone(),
+                        size: UVec2::new(layer.c_wid, layer.c_hei),
+                        tile_size: layer.grid_size as f32,
+                        data: layer.int_grid_csv.clone(),
+                    });
+                }
+                LayerType::Entities => {
+                    for ent in &layer.entity_instances {
+                        let min = Vec2::new(ent.px.0 as f32, ent.px.1 as f32);
+                        let size = Vec2::new(ent.width as f32, ent.height as f32);
+                        let settings: serde_json::Value = ent
+                            .field_instances
+                            .iter()
+                            .map(|f| (f.identifier.clone(), f.value.clone()))
+                            .collect();
+                        let kind = match ent.identifier.as_str() {
+                            "Player" => {
+                                spawn = Some(min + size * 0.5);
+                                continue;
+                            }
+                            "Fan" => {
+                                winds.push(Wind::from_settings(min + size * 0.5, &settings));
+                                continue;
+                            }
+                            "Door" => ItemKind::Door,
+                            "Spikes" => ItemKind::Spikes,
+                            "Inflator" => {
+                                ItemKind::Inflator(Pump::new(Refill::from_settings(&settings)))
+                            }
+                            _ => continue,
+                        };
+                        items.push(Item {
+                            iid: ent.iid.clone(),
+                            kind,
+                            bounds: Rect {
+                                min,
+                                max: min + size,
+                            },
+                            alive: true,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        let spawn = spawn.ok_or_else(|| anyhow!("Level {} has no Player", level.identifier))?;
+
+        let balloon = Balloon::new(tuning);
+        let body = Body {
+            pos: spawn,
+            size: balloon.size().min(PLAYER_SIZE),
+            gravity: 1.0,
+            mass: 1.0,
+            ..Default::default()
+        };
+        Ok(Self {
+            map,
+            balloon,
+            body,
+            air: AirTank::empty(tuning.air_capacity),
+            air_capacity: tuning.air_capacity,
+            upgrades: BTreeMap::new(),
+            gravity: tuning.gravity,
+            items,
+            winds,
+            previous: Controls::default(),
+            state: SimState::Running,
+            frame: 0,
+        })
+    }
+
+    pub fn body(&self) -> &Body {
+        &self.body
+    }
+
+    pub fn air(&self) -> AirTank {
+        self.air
+    }
+
+    pub fn state(&self) -> SimState {
+        self.state
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Advance one tick holding the pressed actions of `controls`
+    pub fn step(&mut self, controls: Controls) -> SimState {
+        let controls = controls.after(&self.previous);
+        self.advance(controls, SIM_TICK)
+    }
+
+    /// Air capacity upgrades owned, new ones are added as upgrading pumps are used
+    pub fn upgrades(&self) -> &BTreeMap<String, f32> {
+        &self.upgrades
+    }
+
+    /// Start with the air capacity `upgrades` of earlier attempts
+    pub fn set_upgrades(&mut self, upgrades: BTreeMap<String, f32>) {
+        self.upgrades = upgrades;
+        self.air.capacity = self.air_capacity + self.upgrades.values().sum::<f32>();
+        self.air.amount = self.air.amount.min(self.air.capacity);
+    }
+
+    /// Start over with `tuning`, the overrides of the level aren't applied
+    fn set_tuning(&mut self, tuning: Tuning) {
+        self.balloon = Balloon::new(tuning);
+        self.body.size = self.balloon.size().min(PLAYER_SIZE);
+        self.air_capacity = tuning.air_capacity;
+        self.air = AirTank::empty(tuning.air_capacity);
+        self.gravity = tuning.gravity;
+    }
+
+    /// Start from a checkpoint like the player respawning there
+    pub fn respawn(&mut self, respawn: &Respawn) {
+        self.body.pos = respawn.pos();
+        if let Some(size) = respawn.original_size {
+            self.balloon.original_size = Vec2::from(size);
+        }
+        self.balloon
+            .set_inflation_rate(respawn.inflation, &mut self.body);
+        self.air.amount = respawn.air.min(self.air.capacity);
+        for item in &mut self.items {
+            if respawn.removed.contains(&item.iid) {
+                item.alive = false;
+            }
+        }
+    }
+
+    /// Feed a recorded attempt frame by frame with its own ticks and tuning, from its door spawn or
+    /// checkpoint if any
+    pub fn play(&mut self, replay: &Replay) -> SimState {
+        if let Some(tuning) = replay.tuning {
+            self.set_tuning(tuning);
+        }
+        self.set_upgrades(replay.upgrades.clone());
+        if let Some(pos) = replay.spawn_point {
+            self.body.pos = Vec2::from(pos);
+        }
+        if let Some(respawn) = replay.checkpoint.as_ref() {
+            self.respawn(respawn);
+        }
+        for (controls, tick) in replay.iter() {
+            if self.advance(controls, tick) != SimState::Running {
+                break;
+            }
+        }
+        self.state
+    }
+
+    fn advance(&mut self, controls: Controls, tick: f32) -> SimState {
+        if self.state != SimState::Running {
+            return self.state;
+        }
+        self.previous = controls;
+
+        let step = self.balloon.step(
+            &controls,
+            &mut self.body,
+            &mut self.air,
+            self.map.as_ref(),
+            &[],
+            tick,
+        );
+        if step.restart || step.burst {
+            self.state = SimState::Dead;
+            return self.state;
+        }
+        let wind: Vec2 = self.winds.iter().map(|w| w.accel(&self.body)).sum();
+        self.body.accel += wind;
+        let popped = self.base_update(tick);
+        let left = self
+            .map
+            .as_ref()
+            .is_some_and(|map| out_of_bounds(&self.body, map.bounds()));
+        if popped || left {
+            self.state = SimState::Dead;
+            return self.state;
+        }
+        for item in &mut self.items {
+            if let ItemKind::Inflator(pump) = &mut item.kind {
+                pump.update(tick);
+            }
+        }
+        self.touch_items();
+        self.frame += 1;
+        self.state
+    }
+
+    /// Step until the run ends or `ticks` elapsed, `script` gives the controls of each frame
+    pub fn run<F: FnMut(usize) -> Controls>(&mut self, ticks: usize, mut script: F) -> SimState {
+        for _ in 0..ticks {
+            if self.step(script(self.frame)) != SimState::Running {
+                break;
+            }
+        }
+        self.state
+    }
+
+    fn touch_items(&mut self) {
+        let bounds = self.body.bounds();
+        for item in self.items.iter_mut().filter(|item| item.alive) {
+            let touching = !(bounds.min.x >= item.bounds.max.x
+                || bounds.max.x <= item.bounds.min.x
+                || bounds.min.y >= item.bounds.max.y
+                || bounds.max.y <= item.bounds.min.y);
+            if !touching {
+                continue;
+            }
+            match item.kind {
+                ItemKind::Door => self.state = SimState::ReachedDoor,
+                ItemKind::Spikes => self.state = SimState::Dead,
+                ItemKind::Inflator(ref mut pump) => {
+                    let upgraded = self.upgrades.contains_key(&item.iid);
+                    let Some(raise) = pump.use_on(&mut self.air, upgraded) else {
+                        continue;
+                    };
+                    if raise > 0.0 {
+                        self.upgrades.insert(item.iid.clone(), raise);
+                    }
+                    if !pump.reusable() {
+                        item.alive = false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move the body, true if it popped on a sharp tile
+    fn base_update(&mut self, tick: f32) -> bool {
+        let body = &mut self.body;
+        let vel = body.vel;
+        body.vel.y += self.gravity * body.gravity * tick;
+        let fric = Vec2::new(
+            (body.friction.x * tick).min(1.0),
+            (body.friction.y * tick).min(1.0),
+        );
+        body.vel = body.vel + (body.accel * tick - body.vel * fric);
+        let vstep = (vel + body.vel) * (tick * 0.5);
+        body.on_ground = false;
+
+        let Some(map) = self.map.as_ref() else {
+            body.pos += vstep;
+            return false;
+        };
+        let pops = |t: &Trace| {
+            t.tile != 0
+                && map
+                    .get(t.tile_pos)
+                    .is_some_and(|tile| self.balloon.pops_on(tile))
+        };
+        let t = trace(map, body.pos, vstep, body.size);
+        handle_trace_result(body, &t);
+        let mut popped = pops(&t);
+        // slide along tiles with the remaining velocity
+        if t.length < 1. {
+            let rotated_normal = Vec2::new(-t.normal.y, t.normal.x);
+            let vel_along_normal = vstep.dot(rotated_normal);
+
+            if vel_along_normal != 0. {
+                let remaining = 1. - t.length;
+                let vstep2 = rotated_normal * (vel_along_normal * remaining);
+                let t2 = trace(map, body.pos, vstep2, body.size);
+                handle_trace_result(body, &t2);
+                popped |= pops(&t2);
+            }
+        }
+        popped
+    }
+}
+
+fn handle_trace_result(body: &mut Body, t: &Trace) {
+    body.pos = t.pos;
+
+    if t.tile == 0 {
+        return;
+    }
+
+    if body.restitution > 0. {
+        let vel_against_normal = body.vel.dot(t.normal);
+
+        if vel_against_normal.abs() * body.restitution > ENTITY_MIN_BOUNCE_VELOCITY {
+            let vn = t.normal * vel_against_normal * 2.;
+            body.vel = (body.vel - vn) * body.restitution;
+            return;
+        }
+    }
+
+    if t.normal.y < -MAX_GROUND_NORMAL {
+        body.on_ground = true;
+        if t.normal.y < -MIN_SLIDE_NORMAL {
+            body.vel.y = body.vel.x * t.normal.x;
+        }
+    }
+
+    let rotated_normal = Vec2::new(-t.normal.y, t.normal.x);
+    let vel_along_normal = body.vel.dot(rotated_normal);
+    body.vel = rotated_normal * vel_along_normal;
+}
+
+/// Sweep an AABB through the collision map, a port of roast-2d 0.0.5's `trace`
+fn trace(map: &CollisionMap, from_center: Vec2, vel: Vec2, size: Vec2) -> Trace {
+    let half_size = size * 0.5;
+    let from = from_center - half_size;
+    let to = from + vel;
+    let mut res = Trace {
+        pos: to,
+        ..Default::default()
+    };
+
+    let map_size = map.bounds();
+    if (from.x + size.x < 0. && to.x + size.x < 0.)
+        || (from.y + size.y < 0. && to.y + size.y < 0.)
+        || (from.x > map_size.x && to.x > map_size.x)
+        || (from.y > map_size.y && to.y > map_size.y)
+        || (vel.x == 0. && vel.y == 0.)
+    {
+        res.pos += half_size;
+        return res;
+    }
+    let offset = Vec2::new(
+        if vel.x > 0. { 1.0 } else { 0.0 },
+        if vel.y > 0. { 1.0 } else { 0.0 },
+    );
+    let corner = from + size * offset;
+    let dir = offset * -2.0 + Vec2::splat(1.0);
+    let max_vel = (vel.x * -dir.x).max(vel.y * -dir.y);
+    let steps = (max_vel / map.tile_size).ceil();
+
+    if steps == 0.0 {
+        res.pos += half_size;
+        return res;
+    }
+
+    let step_size = vel / steps;
+
+    let mut last_tile_pos = IVec2::splat(-16);
+    let mut extra_step_for_slope = false;
+    for i in 0..=(steps as usize) {
+        let tile_pos: IVec2 = {
+            let p = (corner + step_size * i as f32) / map.tile_size;
+            IVec2::new(p.x as i32, p.y as i32)
+        };
+
+        let mut corner_tile_checked = 0;
+        if last_tile_pos.x != tile_pos.x {
+            let mut max_y = from.y + size.y * (1. - offset.y);
+            if i > 0 {
+                max_y += (vel.y / vel.x)
+                    * ((tile_pos.x as f32 + 1. - offset.x) * map.tile_size - corner.x);
+            }
+
+            let num_tiles = (max_y / map.tile_size - tile_pos.y as f32 - offset.y)
+                .abs()
+                .ceil() as i32;
+            for t in 0..num_tiles {
+                check_tile(
+                    map,
+                    from,
+                    vel,
+                    size,
+                    IVec2::new(tile_pos.x, tile_pos.y + dir.y as i32 * t),
+                    &mut res,
+                );
+            }
+
+            last_tile_pos.x = tile_pos.x;
+            corner_tile_checked = 1;
+        }
+
+        if last_tile_pos.y != tile_pos.y {
+            let mut max_x = from.x + size.x * (1. - offset.x);
+            if i > 0 {
+                max_x += (vel.x / vel.y)
+                    * ((tile_pos.y as f32 + 1. - offset.y) * map.tile_size - corner.y);
+            }
+
+            let num_tiles = (max_x / map.tile_size - tile_pos.x as f32 - offset.x)
+                .abs()
+                .ceil() as i32;
+            for t in corner_tile_checked..num_tiles {
+                check_tile(
+                    map,
+                    from,
+                    vel,
+                    size,
+                    IVec2::new(tile_pos.x + dir.x as i32 * t, tile_pos.y),
+                    &mut res,
+                );
+            }
+
+            last_tile_pos.y = tile_pos.y;
+        }
+
+        if res.tile > 0 && (res.tile == 1 || extra_step_for_slope) {
+            res.pos += half_size;
+            return res;
+        }
+        extra_step_for_slope = true;
+    }
+
+    res.pos += half_size;
+    res
+}
+
+fn check_tile(
+    map: &CollisionMap,
+    pos: Vec2,
+    vel: Vec2,
+    size: Vec2,
+    tile_pos: IVec2,
+    res: &mut Trace,
+) {
+    if map.get(tile_pos).unwrap_or_default() == 0 {
+        return;
+    }
+
+    let mut rp: Vec2 = Vec2::new(
+        tile_pos.x as f32 * map.tile_size,
+        tile_pos.y as f32 * map.tile_size,
+    ) + Vec2::new(
+        if vel.x > 0. { -size.x } else { map.tile_size },
+        if vel.y > 0. { -size.y } else { map.tile_size },
+    );
+
+    let length;
+    let sign = (vel.x * (rp.y - pos.y) - vel.y * (rp.x - pos.x)) * vel.x * vel.y;
+
+    if sign < 0. || vel.y == 0. {
+        length = ((pos.x - rp.x) / vel.x).abs();
+        if length > res.length {
+            return;
+        };
+
+        rp.y = pos.y + length * vel.y;
+        res.normal = Vec2::new(if vel.x > 0.0 { -1.0 } else { 1.0 }, 0.0);
+    } else {
+        length = ((pos.y - rp.y) / vel.y).abs();
+        if length > res.length {
+            return;
+        };
+
+        rp.x = pos.x + length * vel.x;
+        res.normal = Vec2::new(0.0, if vel.y > 0.0 { -1.0 } else { 1.0 });
+    }
+
+    res.tile = 1;
+    res.tile_pos = tile_pos;
+    res.length = length;
+    res.pos = rp;
+}
@@ -0,0 +1,117 @@
+use roast_2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{export_file, read_storage, write_storage};
+
+const EXPORT_KEY: &str = "splits_export";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Split {
+    pub level: String,
+    /// Seconds spent in the level, deaths included
+    pub segment: f32,
+}
+
+/// Level by level times of a run
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Splits {
+    pub splits: Vec<Split>,
+}
+
+/// A row of an exported splits file
+#[derive(Serialize)]
+struct ExportedSplit<'a> {
+    level: &'a str,
+    segment: f32,
+    /// Run time when the level was left
+    split: f32,
+    /// Segment compared with the personal best
+    delta: Option<f32>,
+}
+
+impl Splits {
+    pub fn push(&mut self, level: String, segment: f32) {
+        self.splits.push(Split { level, segment });
+    }
+
+    pub fn total(&self) -> f32 {
+        self.splits.iter().map(|split| split.segment).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.splits.is_empty()
+    }
+
+    pub fn segment(&self, level: &str) -> Option<f32> {
+        self.splits
+            .iter()
+            .find(|split| split.level == level)
+            .map(|split| split.segment)
+    }
+
+    /// Time ahead (negative) or behind `pb` over the levels of this run, `None` if `pb` misses one
+    pub fn delta(&self, pb: &Splits) -> Option<f32> {
+        self.splits.iter().try_fold(0.0, |delta, split| {
+            Some(delta + split.segment - pb.segment(&split.level)?)
+        })
+    }
+
+    /// A run of the whole campaign can become the personal best
+    pub fn beats(&self, pb: Option<&Splits>, first_level: &str) -> bool {
+        let complete = self
+            .splits
+            .first()
+            .is_some_and(|split| split.level == first_level);
+        complete && pb.map_or(true, |pb| self.total() < pb.total())
+    }
+
+    fn export_json(&self, pb: Option<&Splits>) -> Result<String> {
+        let mut split = 0.0;
+        let rows: Vec<ExportedSplit> = self
+            .splits
+            .iter()
+            .map(|s| {
+                split += s.segment;
+                ExportedSplit {
+                    level: &s.level,
+                    segment: s.segment,
+                    split,
+                    delta: pb
+                        .and_then(|pb| pb.segment(&s.level))
+                        .map(|best| s.segment - best),
+                }
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&rows)?)
+    }
+}
+
+fn pb_key(slot: usize) -> String {
+    format!("splits_{slot}")
+}
+
+pub fn load_pb(slot: usize) -> Option<Splits> {
+    let data = read_storage(&pb_key(slot))?;
+    match serde_json::from_str(&data) {
+        Ok(splits) => Some(splits),
+        Err(err) => {
+            log::error!("Can't read personal best err {err:?}");
+            None
+        }
+    }
+}
+
+pub fn save_pb(slot: usize, splits: &Splits) {
+    let res = serde_json::to_string(splits)
+        .map_err(Error::from)
+        .and_then(|data| write_storage(&pb_key(slot), &data));
+    if let Err(err) = res {
+        log::error!("Can't write personal best err {err:?}");
+    }
+}
+
+/// Export the splits of a run compared with `pb`, next to the saves or as a download on the web
+pub fn export(splits: &Splits, pb: Option<&Splits>) -> Result<()> {
+    let data = splits.export_json(pb)?;
+    export_file(EXPORT_KEY, &data)
+}
@@ -0,0 +1,112 @@
+use roast_2d::prelude::*;
+
+use crate::{Action, Controls};
+
+/// Share of the D-pad radius around its center that presses nothing
+const DPAD_DEADZONE: f32 = 0.3;
+const DPAD_RADIUS: f32 = 64.0;
+const BUTTON_RADIUS: f32 = 30.0;
+const MARGIN: f32 = 16.0;
+
+/// Scale from view to window pixels with `ScaleMode::Exact`, the same on both axes
+pub fn screen_scale(window: Vec2, view: Vec2) -> f32 {
+    (window.x / view.x).min(window.y / view.y)
+}
+
+/// Size of the screen in view coordinates, the window resizes it so it's wider or taller than
+/// the view on other aspect ratios
+pub fn logical_size(window: Vec2, view: Vec2) -> Vec2 {
+    window / screen_scale(window, view)
+}
+
+/// A round on-screen button
+#[derive(Debug, Clone, Copy)]
+pub struct TouchButton {
+    pub action: Action,
+    pub label: &'static str,
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+/// Layout of the virtual D-pad and buttons, in view coordinates
+#[derive(Debug, Clone)]
+pub struct TouchLayout {
+    pub dpad_center: Vec2,
+    pub dpad_radius: f32,
+    /// Arrows drawn on the D-pad, a touch presses them by its direction from the center
+    pub arrows: [TouchButton; 4],
+    pub buttons: [TouchButton; 4],
+}
+
+impl TouchLayout {
+    /// Lay the controls out along the edges of a screen of `screen_size` view pixels
+    pub fn new(screen_size: Vec2) -> Self {
+        let dpad_center = Vec2::new(MARGIN + DPAD_RADIUS, screen_size.y - MARGIN - DPAD_RADIUS);
+        let arrow = |action, label, dir: Vec2| TouchButton {
+            action,
+            label,
+            center: dpad_center + dir * (DPAD_RADIUS - BUTTON_RADIUS * 0.6),
+            radius: BUTTON_RADIUS * 0.6,
+        };
+        let button = |action, label, center| TouchButton {
+            action,
+            label,
+            center,
+            radius: BUTTON_RADIUS,
+        };
+        let corner = screen_size - Vec2::splat(MARGIN + BUTTON_RADIUS);
+        let step = BUTTON_RADIUS * 2.0 + MARGIN * 0.5;
+        Self {
+            dpad_center,
+            dpad_radius: DPAD_RADIUS,
+            arrows: [
+                arrow(Action::Left, "<", Vec2::NEG_X),
+                arrow(Action::Right, ">", Vec2::X),
+                arrow(Action::Up, "^", Vec2::NEG_Y),
+                arrow(Action::Down, "v", Vec2::Y),
+            ],
+            buttons: [
+                button(Action::Jump, "J", corner),
+                button(Action::Inflate, "+", corner - Vec2::new(0.0, step)),
+                button(Action::Deflate, "-", corner - Vec2::new(step, 0.0)),
+                TouchButton {
+                    action: Action::Pause,
+                    label: "II",
+                    center: Vec2::new(screen_size.x * 0.5, MARGIN + BUTTON_RADIUS * 0.6),
+                    radius: BUTTON_RADIUS * 0.6,
+                },
+            ],
+        }
+    }
+
+    /// Actions held by the touch points, the D-pad presses up to two directions
+    pub fn pressed(&self, touches: &[Vec2]) -> Vec<Action> {
+        let mut actions = Vec::new();
+        for &touch in touches {
+            let offset = (touch - self.dpad_center) / self.dpad_radius;
+            if offset.length() <= 1.0 {
+                let dirs = [
+                    (offset.x < -DPAD_DEADZONE, Action::Left),
+                    (offset.x > DPAD_DEADZONE, Action::Right),
+                    (offset.y < -DPAD_DEADZONE, Action::Up),
+                    (offset.y > DPAD_DEADZONE, Action::Down),
+                ];
+                actions.extend(dirs.into_iter().filter(|(hit, _)| *hit).map(|(_, a)| a));
+                continue;
+            }
+            if let Some(button) = self
+                .buttons
+                .iter()
+                .find(|button| touch.distance(button.center) <= button.radius)
+            {
+                actions.push(button.action);
+            }
+        }
+        actions
+    }
+
+    /// Controls of this tick, `previous` tells which actions are just pressed
+    pub fn controls(&self, touches: &[Vec2], previous: &Controls) -> Controls {
+        Controls::holding(&self.pressed(touches)).after(previous)
+    }
+}
@@ -0,0 +1,84 @@
+use roast_2d::{ldtk::LdtkLevel, prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Gameplay values read from `assets/tuning.json`, missing keys keep their default
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    pub accel_deflation: f32,
+    pub accel_ground: f32,
+    pub accel_air: f32,
+    pub jump_vel: f32,
+    pub jump_high_time: f32,
+    pub jump_high_accel: f32,
+    pub friction_ground: f32,
+    pub friction_air: f32,
+    pub inflation_speed: f32,
+    pub min_inflation: f32,
+    pub max_inflation: f32,
+    /// Inflation of the balloon when a level starts
+    pub start_inflation: f32,
+    /// Air used per second of inflation
+    pub inflator_speed: f32,
+    pub gravity: f32,
+    /// Seconds of inflating against a wall before the balloon pops
+    pub burst_time: f32,
+    /// Inflation from which sharp tiles pop the balloon
+    pub sharp_inflation: f32,
+    /// Air held by a full tank
+    pub air_capacity: f32,
+    /// Air lost per second, levels with a leak override it
+    pub air_leak: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            accel_deflation: 900.0,
+            accel_ground: 600.0,
+            accel_air: 300.0,
+            jump_vel: 200.0,
+            jump_high_time: 0.08,
+            jump_high_accel: 780.0,
+            friction_ground: 2.0,
+            friction_air: 2.0,
+            inflation_speed: 1.2,
+            min_inflation: 1.6,
+            max_inflation: 8.0,
+            start_inflation: 2.8,
+            inflator_speed: 0.5,
+            gravity: 400.0,
+            burst_time: 1.5,
+            sharp_inflation: 5.0,
+            air_capacity: 1.0,
+            air_leak: 0.0,
+        }
+    }
+}
+
+impl Tuning {
+    pub fn from_slice(data: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(data)?)
+    }
+
+    /// Override values with the level fields of the same name, e.g. a Float field `gravity`
+    pub fn for_level(&self, level: &LdtkLevel) -> Self {
+        let Ok(mut value) = serde_json::to_value(self) else {
+            return *self;
+        };
+        for field in &level.field_instances {
+            if let Some(v) = value.get_mut(&field.identifier) {
+                if field.value.is_number() {
+                    *v = field.value.clone();
+                }
+            }
+        }
+        match serde_json::from_value(value) {
+            Ok(tuning) => tuning,
+            Err(err) => {
+                log::error!("Invalid tuning of level {} err {err:?}", level.identifier);
+                *self
+            }
+        }
+    }
+}
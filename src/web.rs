@@ -1,12 +1,139 @@
-use crate::Engine;
-use crate::{app, setup};
-use wasm_bindgen::prelude::wasm_bindgen;
+use std::cell::{Cell, RefCell};
 
-pub fn load_sound_files(eng: &mut Engine) {
-    // Skip for now
+use crate::{app, setup, touch::screen_scale, VIEW_SIZE};
+use roast_2d::prelude::{anyhow::anyhow, Result, Vec2};
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsCast};
+use web_sys::{Blob, HtmlAnchorElement, HtmlCanvasElement, TouchEvent, Url};
+
+const STORAGE_PREFIX: &str = "balloon-game/";
+/// Canvas the engine draws into
+const CANVAS_ID: &str = "roast-2d-canvas";
+
+thread_local! {
+    /// Touch points over the canvas in view coordinates
+    static TOUCHES: RefCell<Vec<Vec2>> = RefCell::new(Vec::new());
+    /// Browsers only start audio after the player interacted with the page
+    static USER_GESTURE: Cell<bool> = const { Cell::new(false) };
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub fn read_storage(key: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{STORAGE_PREFIX}{key}"))
+        .ok()?
+}
+
+pub fn write_storage(key: &str, data: &str) -> Result<()> {
+    let storage = local_storage().ok_or_else(|| anyhow!("localStorage is unavailable"))?;
+    storage
+        .set_item(&format!("{STORAGE_PREFIX}{key}"), data)
+        .map_err(|err| anyhow!("Can't write {key} to localStorage {err:?}"))
+}
+
+/// Download `data` as `{key}.json`, the player can't reach localStorage
+pub fn export_file(key: &str, data: &str) -> Result<()> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| anyhow!("document is unavailable"))?;
+    let parts = js_sys::Array::of1(&data.into());
+    let blob = Blob::new_with_str_sequence(&parts)
+        .map_err(|err| anyhow!("Can't create {key} blob {err:?}"))?;
+    let url = Url::create_object_url_with_blob(&blob)
+        .map_err(|err| anyhow!("Can't create {key} url {err:?}"))?;
+    let link: HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(|err| anyhow!("Can't create {key} link {err:?}"))?
+        .dyn_into()
+        .map_err(|_| anyhow!("Can't create {key} link"))?;
+    link.set_href(&url);
+    link.set_download(&format!("{key}.json"));
+    link.click();
+    Url::revoke_object_url(&url).map_err(|err| anyhow!("Can't release {key} url {err:?}"))
+}
+
+/// Track the touch points of the canvas, the engine only forwards keyboard and mouse events
+fn listen_touches() -> Option<()> {
+    let document = web_sys::window()?.document()?;
+    let canvas: HtmlCanvasElement = document.get_element_by_id(CANVAS_ID)?.dyn_into().ok()?;
+    let handler = Closure::<dyn FnMut(_)>::new({
+        let canvas = canvas.clone();
+        move |event: TouchEvent| {
+            // keep the page from scrolling and zooming under the fingers
+            event.prevent_default();
+            let rect = canvas.get_bounding_client_rect();
+            let size = Vec2::new(rect.width() as f32, rect.height() as f32);
+            let scale = screen_scale(size, VIEW_SIZE);
+            let list = event.touches();
+            let touches = (0..list.length())
+                .filter_map(|i| list.get(i))
+                .map(|touch| {
+                    let x = touch.client_x() as f64 - rect.left();
+                    let y = touch.client_y() as f64 - rect.top();
+                    Vec2::new(x as f32, y as f32) / scale
+                })
+                .collect();
+            TOUCHES.set(touches);
+        }
+    });
+    for event in ["touchstart", "touchmove", "touchend", "touchcancel"] {
+        canvas
+            .add_event_listener_with_callback(event, handler.as_ref().unchecked_ref())
+            .ok()?;
+    }
+    // listens as long as the page lives
+    handler.forget();
+    Some(())
+}
+
+/// Size of the canvas in CSS pixels, the engine sizes its screen after it
+pub fn window_size() -> Option<Vec2> {
+    let canvas = web_sys::window()?
+        .document()?
+        .get_element_by_id(CANVAS_ID)?;
+    let rect = canvas.get_bounding_client_rect();
+    Some(Vec2::new(rect.width() as f32, rect.height() as f32))
+}
+
+pub fn touches() -> Vec<Vec2> {
+    TOUCHES.with_borrow(|touches| touches.clone())
+}
+
+pub fn is_touch_device() -> bool {
+    web_sys::window().is_some_and(|window| window.navigator().max_touch_points() > 0)
+}
+
+/// Unlock audio on the first key press, click or tap
+fn listen_user_gesture() -> Option<()> {
+    let window = web_sys::window()?;
+    let handler = Closure::<dyn FnMut()>::new(|| USER_GESTURE.set(true));
+    for event in ["keydown", "mousedown", "touchend"] {
+        window
+            .add_event_listener_with_callback(event, handler.as_ref().unchecked_ref())
+            .ok()?;
+    }
+    handler.forget();
+    Some(())
+}
+
+pub fn audio_unlocked() -> bool {
+    USER_GESTURE.get()
 }
 
 #[wasm_bindgen(start)]
 pub async fn run_game() {
+    if listen_touches().is_none() {
+        log::error!("Can't listen to touch events");
+    }
+    if listen_user_gesture().is_none() {
+        log::error!("Can't listen to user gestures, audio stays off");
+    }
     app().run(setup).await.unwrap()
 }
+
+/// A page can't close itself, the pause menu hides Quit on the web
+pub fn quit() {
+    log::info!("Quit is not supported on the web");
+}
@@ -0,0 +1,64 @@
+use balloon_game::air::{AirTank, Pump, Refill};
+use serde_json::json;
+
+#[test]
+fn refills_are_capped_by_the_capacity() {
+    let mut tank = AirTank::empty(1.0);
+    let half = Refill {
+        amount: Some(0.5),
+        ..Default::default()
+    };
+    assert!(tank.refill(&half));
+    assert_eq!(tank.amount, 0.5);
+    assert!(tank.refill(&half));
+    assert!(tank.is_full());
+    // a full tank leaves the refill
+    assert!(!tank.refill(&half));
+
+    let upgrade = Refill::from_settings(&json!({ "Capacity": 0.5 }));
+    assert_eq!(upgrade.amount, None);
+    assert!(tank.refill(&upgrade));
+    assert_eq!(tank, AirTank::full(1.5));
+}
+
+#[test]
+fn leaks_drain_the_tank() {
+    let mut tank = AirTank::full(1.0);
+    for _ in 0..60 {
+        tank.leak(0.25, 1.0 / 60.0);
+    }
+    assert!((tank.amount - 0.75).abs() < 1e-3);
+    tank.leak(0.25, 10.0);
+    assert_eq!(tank.amount, 0.0);
+    assert!(!tank.take(0.1));
+}
+
+#[test]
+fn pumps_are_ready_again_after_their_cooldown() {
+    let mut pump = Pump::new(Refill::from_settings(
+        &json!({ "Amount": 0.3, "Cooldown": 1.0 }),
+    ));
+    assert!(pump.reusable());
+    let mut tank = AirTank::empty(1.0);
+    assert_eq!(pump.use_on(&mut tank, false), Some(0.0));
+    assert_eq!(pump.use_on(&mut tank, false), None);
+    assert!(!pump.update(0.5));
+    assert!(pump.update(0.5));
+    assert!(pump.use_on(&mut tank, false).is_some());
+    assert!((tank.amount - 0.6).abs() < 1e-6);
+}
+
+#[test]
+fn pumps_raise_the_capacity_once() {
+    let mut pump = Pump::new(Refill::from_settings(
+        &json!({ "Capacity": 0.5, "Cooldown": 1.0 }),
+    ));
+    let mut tank = AirTank::empty(1.0);
+    assert_eq!(pump.use_on(&mut tank, false), Some(0.5));
+    assert_eq!(tank, AirTank::full(1.5));
+    tank.take(1.0);
+    pump.update(1.0);
+    // the tank was upgraded, the pump only refills it now
+    assert_eq!(pump.use_on(&mut tank, true), Some(0.0));
+    assert_eq!(tank, AirTank::full(1.5));
+}
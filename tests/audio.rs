@@ -0,0 +1,71 @@
+use balloon_game::audio::{
+    impact_rate, impact_volume, level_music, stereo_panning, AudioSettings, Bus, DEFAULT_MUSIC,
+    IMPACT_MIN_SPEED,
+};
+use roast_2d::ldtk::LdtkLevel;
+use serde_json::{json, Value};
+
+fn level(music: Option<Value>) -> LdtkLevel {
+    let fields: Vec<Value> = music
+        .into_iter()
+        .map(|value| json!({ "__identifier": "Music", "__type": "String", "__value": value, "defUid": 1 }))
+        .collect();
+    serde_json::from_value(json!({
+        "identifier": "Level_0",
+        "iid": "level-0",
+        "uid": 0,
+        "bgRelPath": null,
+        "bgColor": null,
+        "fieldInstances": fields,
+        "layerInstances": []
+    }))
+    .unwrap()
+}
+
+#[test]
+fn volume_steps_stay_in_range() {
+    let mut settings = AudioSettings::default();
+    settings.step(Bus::Sfx, 3);
+    assert_eq!(settings.volume(Bus::Sfx), 1.0);
+    settings.step(Bus::Sfx, -4);
+    assert!((settings.volume(Bus::Sfx) - 0.6).abs() < 1e-6);
+    settings.step(Bus::Sfx, -20);
+    assert_eq!(settings.volume(Bus::Sfx), 0.0);
+}
+
+#[test]
+fn mute_silences_the_master_track() {
+    let mut settings = AudioSettings::default();
+    assert_eq!(settings.master_volume(), 1.0);
+    settings.muted = true;
+    assert_eq!(settings.master_volume(), 0.0);
+    // missing keys keep their default
+    let loaded: AudioSettings = serde_json::from_str(r#"{"sfx":0.5}"#).unwrap();
+    assert_eq!(loaded.sfx, 0.5);
+    assert_eq!(loaded.music, AudioSettings::default().music);
+}
+
+#[test]
+fn levels_pick_their_music() {
+    let default = format!("sounds/music/{DEFAULT_MUSIC}");
+    assert_eq!(level_music(&level(None)), Some(default.clone()));
+    assert_eq!(level_music(&level(Some(Value::Null))), Some(default));
+    assert_eq!(
+        level_music(&level(Some(json!("storm.ogg")))),
+        Some("sounds/music/storm.ogg".to_string())
+    );
+    assert_eq!(level_music(&level(Some(json!("")))), None);
+}
+
+#[test]
+fn impacts_scale_with_speed_and_size() {
+    assert!(impact_volume(IMPACT_MIN_SPEED) < impact_volume(300.0));
+    assert_eq!(impact_volume(10_000.0), 1.0);
+    // big balloons thud, small ones ping
+    assert!(impact_rate(1.0) < 1.0 && impact_rate(0.0) > 1.0);
+    // centered on the balloon in the middle of a wide level
+    assert_eq!(stereo_panning(500.0, 500.0, 512.0, 2000.0), 0.5);
+    assert!(stereo_panning(484.0, 500.0, 512.0, 2000.0) < 0.5);
+    // the camera stops at the left side of the level
+    assert_eq!(stereo_panning(100.0, 100.0, 512.0, 2000.0), 100.0 / 512.0);
+}
@@ -0,0 +1,90 @@
+use balloon_game::{
+    analog_strength,
+    bindings::{bindable_keys, raw_key, Bindings, Rebind},
+    Action, ANALOG_DEADZONE,
+};
+use roast_2d::prelude::{ActionId, KeyCode};
+
+#[test]
+fn default_bindings_have_several_keys_per_action() {
+    let bindings = Bindings::default();
+    let left = bindings.keys(Action::Left);
+    assert!(left.starts_with(&[KeyCode::Left, KeyCode::KeyA]));
+    assert!(left.contains(&KeyCode::GamepadLStickLeft));
+    assert_eq!(bindings.action(KeyCode::KeyW), Some(Action::Up));
+    assert_eq!(bindings.action(KeyCode::KeyZ), None);
+}
+
+#[test]
+fn rebinding_resolves_conflicts() {
+    let mut bindings = Bindings::default();
+    assert_eq!(bindings.bind(Action::Jump, KeyCode::KeyZ), Rebind::Added);
+    assert_eq!(
+        bindings.bind(Action::Jump, KeyCode::KeyZ),
+        Rebind::Unchanged
+    );
+    // R is one of two keys of Restart, it moves
+    assert_eq!(
+        bindings.bind(Action::Jump, KeyCode::KeyR),
+        Rebind::Moved(Action::Restart)
+    );
+    assert_eq!(bindings.keys(Action::Restart), [KeyCode::GamepadY]);
+    // Y is the last key of Restart, it stays
+    assert_eq!(
+        bindings.bind(Action::Jump, KeyCode::GamepadY),
+        Rebind::Conflict(Action::Restart)
+    );
+    assert_eq!(bindings.action(KeyCode::GamepadY), Some(Action::Restart));
+    assert_eq!(bindings.unbind_last(Action::Restart), None);
+    assert_eq!(bindings.unbind_last(Action::Jump), Some(KeyCode::KeyR));
+}
+
+#[test]
+fn bindings_roundtrip_through_json() {
+    let mut bindings = Bindings::default();
+    bindings.bind(Action::Inflate, KeyCode::GamepadRTrigger);
+    let data = serde_json::to_string(&bindings).unwrap();
+    let loaded: Bindings = serde_json::from_str(&data).unwrap();
+    assert_eq!(loaded, bindings);
+}
+
+#[test]
+fn captured_keys_never_trigger_actions() {
+    for key in bindable_keys() {
+        assert!(Action::ALL
+            .into_iter()
+            .all(|action| raw_key(key) != ActionId::from(action)));
+    }
+}
+
+#[test]
+fn analog_values_rest_inside_the_deadzone() {
+    assert_eq!(analog_strength(0.0), 0.0);
+    assert_eq!(analog_strength(0.15), 0.0);
+    assert_eq!(analog_strength(ANALOG_DEADZONE), 0.0);
+    assert!((analog_strength(0.6) - 0.5).abs() < 1e-6);
+    assert_eq!(analog_strength(1.0), 1.0);
+}
+
+#[test]
+fn saved_keyboard_bindings_get_the_default_gamepad_buttons() {
+    // saved before gamepads were bound, then Y was given to Pause
+    let data = r#"{"keys":{"Left":[80],"Jump":[44],"Restart":[21],"Pause":[41,109]}}"#;
+    let bindings = serde_json::from_str::<Bindings>(data)
+        .unwrap()
+        .with_defaults();
+    assert!(bindings
+        .keys(Action::Left)
+        .contains(&KeyCode::GamepadLStickLeft));
+    assert_eq!(bindings.action(KeyCode::GamepadA), Some(Action::Jump));
+    assert_eq!(bindings.keys(Action::Restart), [KeyCode::KeyR]);
+    assert_eq!(
+        bindings.keys(Action::Pause),
+        [KeyCode::Escape, KeyCode::GamepadY]
+    );
+    // actions missing from the file get every default key
+    assert_eq!(
+        bindings.keys(Action::ToggleGhost),
+        Bindings::default().keys(Action::ToggleGhost)
+    );
+}
@@ -0,0 +1,80 @@
+use balloon_game::{
+    air::AirTank,
+    checkpoint::{Respawn, RespawnPolicy},
+    replay::Replay,
+    sim::{load_project, Sim},
+};
+use serde_json::json;
+
+fn respawn() -> Respawn {
+    Respawn {
+        checkpoint: "checkpoint".to_string(),
+        pos: [100.0, 60.0],
+        inflation: 4.0,
+        original_size: None,
+        air: 0.25,
+        removed: vec!["inflator".to_string()],
+    }
+}
+
+#[test]
+fn consumed_entities_stay_consumed_by_default() {
+    assert_eq!(
+        RespawnPolicy::from_settings(&json!({})),
+        RespawnPolicy::Keep
+    );
+    assert_eq!(
+        RespawnPolicy::from_settings(&json!({ "Respawn": "Reset" })),
+        RespawnPolicy::Reset
+    );
+    assert_eq!(
+        RespawnPolicy::from_settings(&json!({ "Respawn": "Sometimes" })),
+        RespawnPolicy::Keep
+    );
+}
+
+#[test]
+fn respawning_restores_the_balloon() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    let size = sim.body().size;
+    sim.respawn(&respawn());
+    assert_eq!(sim.body().pos, respawn().pos());
+    assert!(sim.body().size.x > size.x);
+    assert_eq!(
+        sim.air(),
+        AirTank {
+            amount: 0.25,
+            capacity: 1.0
+        }
+    );
+
+    // crowns picked up before the checkpoint keep the balloon grown
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    sim.respawn(&Respawn {
+        original_size: Some([128.0, 128.0]),
+        ..respawn()
+    });
+    let crowned = sim.body().size;
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    sim.respawn(&respawn());
+    assert!(crowned.x > sim.body().size.x);
+
+    // the capacity comes from the upgrades
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    sim.set_upgrades([("pump".to_string(), 0.5)].into());
+    sim.respawn(&respawn());
+    assert_eq!(sim.air().amount, 0.25);
+    assert_eq!(sim.air().capacity, 1.5);
+}
+
+#[test]
+fn replays_keep_their_checkpoint() {
+    let mut replay = Replay::new("Level_0".to_string(), 7);
+    replay.checkpoint = Some(respawn());
+    let parsed = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+    assert_eq!(parsed.checkpoint, Some(respawn()));
+
+    let data = r#"{"version":1,"level":"Level_0","seed":0,"runs":[]}"#;
+    assert_eq!(Replay::from_json(data).unwrap().checkpoint, None);
+}
@@ -0,0 +1,58 @@
+use balloon_game::{
+    death::{out_of_bounds, DeathCause, DeathState, DeathTally},
+    Body,
+};
+use roast_2d::prelude::Vec2;
+
+#[test]
+fn the_level_reloads_once_the_death_animation_ends() {
+    let mut death = DeathState::default();
+    assert!(death.kill(DeathCause::Spikes));
+    // a second death while dying is ignored
+    assert!(!death.kill(DeathCause::Restart));
+    assert_eq!(death.cause(), Some(DeathCause::Spikes));
+    let tick = 0.1;
+    let mut reload = None;
+    let mut ticks = 0;
+    while reload.is_none() {
+        reload = death.update(tick);
+        ticks += 1;
+    }
+    assert_eq!(reload, Some(DeathCause::Spikes));
+    assert_eq!(
+        ticks,
+        (DeathCause::Spikes.duration() / tick).round() as usize
+    );
+    assert_eq!(death, DeathState::Alive);
+    assert_eq!(death.update(tick), None);
+}
+
+#[test]
+fn deaths_are_tallied_per_level_and_cause() {
+    let mut tally = DeathTally::default();
+    tally.record("Level_0", DeathCause::Popped);
+    tally.record("Level_0", DeathCause::Popped);
+    tally.record("Level_0", DeathCause::Spikes);
+    tally.record("Level_1", DeathCause::OutOfBounds);
+    assert_eq!(tally.count("Level_0", DeathCause::Popped), 2);
+    assert_eq!(tally.count("Level_1", DeathCause::Popped), 0);
+    assert_eq!(
+        tally.level("Level_0"),
+        [(DeathCause::Spikes, 1), (DeathCause::Popped, 2)]
+    );
+}
+
+#[test]
+fn balloons_leaving_the_level_are_out_of_bounds() {
+    let body = |x: f32, y: f32| Body {
+        pos: Vec2::new(x, y),
+        size: Vec2::splat(32.0),
+        ..Default::default()
+    };
+    let level = Vec2::new(640.0, 320.0);
+    assert!(!out_of_bounds(&body(320.0, 160.0), level));
+    // still partly inside
+    assert!(!out_of_bounds(&body(320.0, 330.0), level));
+    assert!(out_of_bounds(&body(320.0, 400.0), level));
+    assert!(out_of_bounds(&body(-20.0, 160.0), level));
+}
@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use balloon_game::{
+    audio::parse_sound_banks,
+    events::{EventBus, GameEvent, Impact},
+};
+
+#[test]
+fn every_event_has_existing_sounds() {
+    let banks = parse_sound_banks(&std::fs::read("assets/sounds.json").unwrap()).unwrap();
+    for event in GameEvent::ALL {
+        if event == GameEvent::AirStopped {
+            continue;
+        }
+        let bank = banks
+            .get(event.name())
+            .unwrap_or_else(|| panic!("no sounds for {event:?}"));
+        assert!(!bank.files.is_empty(), "{event:?}");
+        assert!(bank.rate[0] <= bank.rate[1], "{event:?}");
+        for file in &bank.files {
+            assert!(Path::new("assets").join(file).exists(), "{file}");
+        }
+    }
+}
+
+#[test]
+fn drained_events_happen_once_per_frame() {
+    let mut bus = EventBus::default();
+    let first = GameEvent::Bounced(Impact::CENTER);
+    bus.emit(first);
+    bus.emit(GameEvent::PlayerDied);
+    bus.emit(GameEvent::Bounced(Impact {
+        speed: 300.0,
+        ..Impact::CENTER
+    }));
+    assert_eq!(bus.drain(), [first, GameEvent::PlayerDied]);
+    assert!(bus.drain().is_empty());
+}
@@ -0,0 +1,52 @@
+use balloon_game::{fan::Wind, Body};
+use roast_2d::prelude::*;
+use serde_json::json;
+
+fn balloon(pos: Vec2, size: f32) -> Body {
+    Body {
+        pos,
+        size: Vec2::splat(size),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn wind_blows_inside_its_region() {
+    let wind = Wind::from_settings(
+        Vec2::new(100.0, 300.0),
+        &json!({ "Direction": "Up", "Length": 200.0, "Force": 300.0 }),
+    );
+    assert_eq!(wind.dir, Vec2::NEG_Y);
+    let accel = wind.accel(&balloon(Vec2::new(100.0, 200.0), 32.0));
+    assert_eq!(accel, Vec2::new(0.0, -300.0));
+    // beside and beyond the region
+    assert_eq!(
+        wind.accel(&balloon(Vec2::new(200.0, 200.0), 32.0)),
+        Vec2::ZERO
+    );
+    assert_eq!(
+        wind.accel(&balloon(Vec2::new(100.0, 20.0), 32.0)),
+        Vec2::ZERO
+    );
+}
+
+#[test]
+fn inflated_balloons_catch_more_wind() {
+    let wind = Wind::from_settings(Vec2::ZERO, &json!({ "Direction": "Right" }));
+    let small = wind.accel(&balloon(Vec2::new(60.0, 0.0), 10.0));
+    let big = wind.accel(&balloon(Vec2::new(60.0, 0.0), 64.0));
+    assert!(small.x > 0.0 && small.y == 0.0);
+    assert!(big.x > small.x * 6.0);
+}
+
+#[test]
+fn fans_can_start_off() {
+    let pos = Vec2::new(60.0, 0.0);
+    let mut wind = Wind::from_settings(
+        Vec2::ZERO,
+        &json!({ "Direction": "Right", "Blowing": false }),
+    );
+    assert_eq!(wind.accel(&balloon(pos, 32.0)), Vec2::ZERO);
+    wind.blowing = true;
+    assert!(wind.accel(&balloon(pos, 32.0)).x > 0.0);
+}
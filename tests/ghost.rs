@@ -0,0 +1,15 @@
+use balloon_game::ghost::{GhostRun, GHOST_INTERVAL};
+use roast_2d::prelude::Vec2;
+
+#[test]
+fn ghost_interpolates_between_samples() {
+    let mut run = GhostRun::default();
+    let size = Vec2::splat(32.0);
+    run.record(0.0, Vec2::new(0.0, 0.0), size);
+    run.record(GHOST_INTERVAL, Vec2::new(10.0, 0.0), size);
+
+    let (pos, _) = run.sample(GHOST_INTERVAL * 0.5).unwrap();
+    assert!((pos.x - 5.0).abs() < 1e-3);
+    assert_eq!(run.sample(GHOST_INTERVAL).unwrap().0, Vec2::new(10.0, 0.0));
+    assert!(run.sample(GHOST_INTERVAL * 2.0).is_none());
+}
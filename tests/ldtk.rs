@@ -0,0 +1,55 @@
+use std::fs;
+
+use balloon_game::sim::load_project;
+use serde_json::Value;
+
+fn identifiers(defs: &Value) -> Vec<&str> {
+    defs.as_array()
+        .unwrap()
+        .iter()
+        .map(|def| def["identifier"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn entities_define_the_documented_fields() {
+    let data = fs::read_to_string("assets/game.ldtk").unwrap();
+    let proj: Value = serde_json::from_str(&data).unwrap();
+    let defs = &proj["defs"];
+    let documented: [(&str, &[&str]); 7] = [
+        ("Door", &["Level", "Spawn"]),
+        ("Button", &["Targets", "Mode", "Duration", "Respawn"]),
+        ("Inflator", &["Amount", "Capacity", "Cooldown", "Respawn"]),
+        ("Checkpoint", &[]),
+        ("Fan", &["Direction", "Length", "Width", "Force", "Blowing"]),
+        (
+            "MovingPlatform",
+            &["Path", "Mode", "Speed", "Pause", "Running"],
+        ),
+        ("Gate", &["Open"]),
+    ];
+    for (entity, fields) in documented {
+        let def = defs["entities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|def| def["identifier"] == entity)
+            .unwrap_or_else(|| panic!("{entity} is not defined"));
+        assert_eq!(identifiers(&def["fieldDefs"]), fields, "{entity}");
+    }
+    assert_eq!(identifiers(&defs["levelFields"]), ["Music"]);
+}
+
+#[test]
+fn every_entity_is_placed_in_a_level() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    for def in &proj.defs.entities {
+        let placed = proj
+            .levels
+            .iter()
+            .flat_map(|level| &level.layer_instances)
+            .flat_map(|layer| &layer.entity_instances)
+            .any(|ent| ent.identifier == def.identifier);
+        assert!(placed, "{} is never placed", def.identifier);
+    }
+}
@@ -0,0 +1,43 @@
+use balloon_game::platform::{PathMode, PlatformPath};
+use roast_2d::prelude::*;
+use serde_json::json;
+
+const TICK: f32 = 0.1;
+
+/// Positions of a platform stepped `ticks` times from the first waypoint
+fn positions(mut path: PlatformPath, start: Vec2, ticks: usize) -> Vec<Vec2> {
+    let mut pos = start;
+    (0..ticks)
+        .map(|_| {
+            pos += path.velocity(pos, TICK) * TICK;
+            pos
+        })
+        .collect()
+}
+
+#[test]
+fn platforms_ping_pong_and_pause_on_waypoints() {
+    let points = vec![Vec2::ZERO, Vec2::new(10.0, 0.0)];
+    let path = PlatformPath::new(points, PathMode::PingPong, 50.0, 0.2);
+    let xs: Vec<f32> = positions(path, Vec2::ZERO, 8).iter().map(|p| p.x).collect();
+    assert_eq!(xs[..2], [5.0, 10.0]);
+    // two ticks of pause then back
+    assert_eq!(xs[2..6], [10.0, 10.0, 5.0, 0.0]);
+}
+
+#[test]
+fn looping_platforms_go_back_to_the_start() {
+    let points = vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+    let path = PlatformPath::new(points, PathMode::Loop, 200.0, 0.0);
+    let visited = positions(path, Vec2::ZERO, 4);
+    assert_eq!(visited[1], Vec2::new(10.0, 10.0));
+    assert_eq!(visited[2], Vec2::ZERO);
+}
+
+#[test]
+fn ldtk_points_are_cell_centers() {
+    let settings = json!({ "Path": [{ "cx": 3, "cy": 1 }], "Speed": 1000.0, "Pause": 0.0 });
+    let path = PlatformPath::from_settings(Vec2::new(16.0, 48.0), &settings, 32.0);
+    let visited = positions(path, Vec2::new(16.0, 48.0), 1);
+    assert_eq!(visited[0], Vec2::new(112.0, 48.0));
+}
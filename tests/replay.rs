@@ -0,0 +1,91 @@
+use std::fs;
+
+use balloon_game::{
+    replay::Replay,
+    sim::{load_project, Sim, SimState, SIM_TICK},
+    tuning::Tuning,
+    Action, Controls,
+};
+
+#[test]
+fn recorded_hops_reach_the_door_in_level_0() {
+    let data = fs::read_to_string("tests/replays/level_0_hop_right.json").unwrap();
+    let replay = Replay::from_json(&data).unwrap();
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, &replay.level).unwrap();
+    assert_eq!(sim.play(&replay), SimState::ReachedDoor);
+}
+
+#[test]
+fn replay_survives_a_round_trip() {
+    let mut replay = Replay::new("Level_1".to_string(), 42);
+    for frame in 0..180 {
+        let controls = match frame {
+            0..=59 => Controls::holding(&[Action::Right]),
+            60..=69 => Controls::holding(&[Action::Right, Action::Jump]),
+            70..=99 => Controls::holding(&[Action::Up]).with_strength(Action::Deflate, 0.4),
+            _ => Controls::holding(&[Action::Deflate, Action::Up]),
+        };
+        replay.push(&controls, SIM_TICK);
+    }
+    let parsed = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+    assert_eq!(parsed, replay);
+    assert_eq!(parsed.frames(), 180);
+
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut a = Sim::new(&proj, &replay.level).unwrap();
+    let mut b = Sim::new(&proj, &parsed.level).unwrap();
+    a.play(&replay);
+    b.play(&parsed);
+    assert_eq!(a.frame(), 180);
+    assert_eq!(a.body().pos, b.body().pos);
+}
+
+#[test]
+fn rejects_unknown_versions() {
+    let data = r#"{"version":99,"level":"Level_0","seed":0,"runs":[]}"#;
+    assert!(Replay::from_json(data).is_err());
+}
+
+#[test]
+fn replays_start_at_the_door_spawn() {
+    let mut replay = Replay::new("Level_0".to_string(), 7);
+    replay.spawn_point = Some([200.0, 40.0]);
+    let parsed = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+    assert_eq!(parsed.spawn_point, replay.spawn_point);
+
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, &parsed.level).unwrap();
+    sim.play(&parsed);
+    assert_eq!(<[f32; 2]>::from(sim.body().pos), [200.0, 40.0]);
+}
+
+#[test]
+fn replays_play_with_their_recorded_tuning() {
+    let tuning = Tuning {
+        gravity: Tuning::default().gravity * 2.0,
+        ..Default::default()
+    };
+    let mut untuned = Replay::new("Level_0".to_string(), 3);
+    for frame in 0..25 {
+        let controls = match frame {
+            5..=14 => Controls::holding(&[Action::Jump]),
+            _ => Controls::default(),
+        };
+        untuned.push(&controls, SIM_TICK);
+    }
+    let mut replay = untuned.clone();
+    replay.tuning = Some(tuning);
+    let parsed = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+    assert_eq!(parsed.tuning, Some(tuning));
+
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut played = Sim::new(&proj, &parsed.level).unwrap();
+    played.play(&parsed);
+    let mut heavy = Sim::with_tuning(&proj, "Level_0", &tuning).unwrap();
+    heavy.play(&untuned);
+    let mut light = Sim::new(&proj, "Level_0").unwrap();
+    light.play(&untuned);
+    assert_eq!(played.body().pos, heavy.body().pos);
+    assert_ne!(played.body().pos, light.body().pos);
+}
@@ -0,0 +1,30 @@
+use balloon_game::save::{parse_save, SAVE_VERSION};
+
+#[test]
+fn version_1_saves_unlock_every_level_up_to_the_highest() {
+    let data = r#"{"version":1,"progress":{"highest_unlocked":2,"levels":{}}}"#;
+    let progress = parse_save(data).unwrap();
+    assert_eq!(progress.last_level.as_deref(), Some("Level_2"));
+    let unlocked: Vec<_> = progress.unlocked.iter().map(String::as_str).collect();
+    assert_eq!(unlocked, ["Level_0", "Level_1", "Level_2"]);
+}
+
+#[test]
+fn current_saves_load_as_they_are() {
+    let data = format!(
+        r#"{{"version":{SAVE_VERSION},"progress":{{"last_level":"Level_3","unlocked":["Level_3"],"levels":{{"Level_3":{{"deaths":4,"completed":false,"best_time":null}}}},"hide_ghost":true}}}}"#
+    );
+    let progress = parse_save(&data).unwrap();
+    assert_eq!(progress.last_level.as_deref(), Some("Level_3"));
+    assert_eq!(progress.levels["Level_3"].deaths, 4);
+    assert!(progress.hide_ghost);
+}
+
+#[test]
+fn rejects_unknown_versions() {
+    let newer = format!(r#"{{"version":{},"progress":{{}}}}"#, SAVE_VERSION + 1);
+    assert!(parse_save(&newer).is_err());
+    assert!(parse_save(r#"{"version":0,"progress":{}}"#).is_err());
+    assert!(parse_save(r#"{"progress":{}}"#).is_err());
+    assert!(parse_save("not json").is_err());
+}
@@ -0,0 +1,225 @@
+use balloon_game::{
+    air::AirTank,
+    sim::{load_project, Sim, SimState, SIM_TICK},
+    Action, Balloon, Body, Controls, SHARP_TILE,
+};
+use roast_2d::prelude::{glam::UVec2, CollisionMap, Rect, Vec2};
+
+fn seconds(secs: f32) -> usize {
+    (secs / SIM_TICK) as usize
+}
+
+/// Hold right and press jump once a second
+fn hop_right(frame: usize) -> Controls {
+    if frame % 60 < 10 {
+        Controls::holding(&[Action::Right, Action::Jump])
+    } else {
+        Controls::holding(&[Action::Right])
+    }
+}
+
+#[test]
+fn hopping_right_in_level_0_reaches_the_door() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    assert_eq!(sim.run(seconds(5.0), hop_right), SimState::ReachedDoor);
+}
+
+#[test]
+fn idle_balloon_rests_on_the_ground() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    let spawn = sim.body().pos;
+    assert_eq!(
+        sim.run(seconds(2.0), |_| Controls::default()),
+        SimState::Running
+    );
+    assert!(sim.body().on_ground);
+    assert_eq!(sim.body().pos.x, spawn.x);
+}
+
+#[test]
+fn inflating_without_air_keeps_the_size() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, "Level_1").unwrap();
+    let size = sim.body().size;
+    sim.run(seconds(2.0), |_| Controls::holding(&[Action::Inflate]));
+    assert_eq!(sim.air().amount, 0.0);
+    assert_eq!(sim.body().size, size);
+}
+
+#[test]
+fn half_tilted_stick_walks_slower() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut full = Sim::new(&proj, "Level_0").unwrap();
+    let mut half = Sim::new(&proj, "Level_0").unwrap();
+    let spawn = full.body().pos;
+    full.run(seconds(0.5), |_| Controls::holding(&[Action::Right]));
+    half.run(seconds(0.5), |_| {
+        Controls::default().with_strength(Action::Right, 0.5)
+    });
+    assert!(half.body().pos.x > spawn.x);
+    assert!(half.body().pos.x < full.body().pos.x);
+}
+
+#[test]
+fn half_pulled_trigger_inflates_at_half_rate() {
+    let inflate = |strength: f32| {
+        let mut balloon = Balloon::default();
+        let mut body = Body {
+            size: balloon.size(),
+            ..Default::default()
+        };
+        let mut air = AirTank::full(1.0);
+        let controls = Controls::default().with_strength(Action::Inflate, strength);
+        balloon.step(&controls, &mut body, &mut air, None, &[], SIM_TICK);
+        (1.0 - air.amount, balloon.size().x)
+    };
+    let (full_air, full_size) = inflate(1.0);
+    let (half_air, half_size) = inflate(0.5);
+    let start = Balloon::default().size().x;
+    assert!((half_air * 2.0 - full_air).abs() < 1e-3);
+    assert!(half_size > start && half_size < full_size);
+}
+
+#[test]
+fn walking_into_spikes_kills() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, "Level_5").unwrap();
+    assert_eq!(
+        sim.run(seconds(2.0), |_| Controls::holding(&[Action::Right])),
+        SimState::Dead
+    );
+}
+
+#[test]
+fn restart_ends_the_run() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut sim = Sim::new(&proj, "Level_0").unwrap();
+    assert_eq!(
+        sim.step(Controls::holding(&[Action::Restart])),
+        SimState::Dead
+    );
+}
+
+#[test]
+fn runs_are_deterministic() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let mut a = Sim::new(&proj, "Level_0").unwrap();
+    let mut b = Sim::new(&proj, "Level_0").unwrap();
+    for frame in 0..seconds(3.0) {
+        a.step(hop_right(frame));
+        b.step(hop_right(frame));
+        assert_eq!(a.body().pos, b.body().pos);
+        assert_eq!(a.body().vel, b.body().vel);
+    }
+}
+
+#[test]
+fn solid_entities_stop_the_inflation() {
+    let inflate = |solids: &[Rect]| {
+        let mut balloon = Balloon::default();
+        let mut body = Body {
+            size: balloon.size(),
+            ..Default::default()
+        };
+        let mut air = AirTank::full(1.0);
+        let controls = Controls::default().with_strength(Action::Inflate, 1.0);
+        balloon.step(&controls, &mut body, &mut air, None, solids, SIM_TICK);
+        balloon.size().x
+    };
+    let start = Balloon::default().size().x;
+    let top = -start * 0.5;
+    // a platform right above the balloon
+    let platform = Rect {
+        min: Vec2::new(-32.0, top - 16.0),
+        max: Vec2::new(32.0, top),
+    };
+    assert_eq!(inflate(&[platform]), start);
+    // resting on a platform doesn't block it
+    let floor = Rect {
+        min: Vec2::new(-32.0, -top),
+        max: Vec2::new(32.0, -top + 16.0),
+    };
+    assert!(inflate(&[floor]) > start);
+}
+
+/// A 32px cell walled on every side by `wall` tiles
+fn boxed_in(wall: u16) -> CollisionMap {
+    let mut data = vec![wall; 9];
+    data[4] = 0;
+    CollisionMap {
+        name: "Collision".to_string(),
+        size: UVec2::new(3, 3),
+        tile_size: 32.0,
+        data,
+    }
+}
+
+/// Inflate for `secs` in a walled cell, true once the balloon burst
+fn inflate_boxed_in(secs: f32) -> bool {
+    let map = boxed_in(1);
+    let mut balloon = Balloon::default();
+    let mut body = Body {
+        pos: Vec2::new(48.0, 48.0),
+        size: balloon.size(),
+        ..Default::default()
+    };
+    let mut air = AirTank::full(1.0);
+    let controls = Controls::default().with_strength(Action::Inflate, 1.0);
+    (0..seconds(secs)).any(|_| {
+        balloon
+            .step(&controls, &mut body, &mut air, Some(&map), &[], SIM_TICK)
+            .burst
+    })
+}
+
+#[test]
+fn blocked_inflation_builds_pressure_until_the_balloon_pops() {
+    assert!(!inflate_boxed_in(0.5));
+    assert!(inflate_boxed_in(3.0));
+}
+
+#[test]
+fn sharp_tiles_only_pop_inflated_balloons() {
+    let mut balloon = Balloon::default();
+    assert!(!balloon.pops_on(SHARP_TILE));
+    let mut body = Body {
+        size: balloon.size(),
+        ..Default::default()
+    };
+    let mut air = AirTank::full(1.0);
+    let controls = Controls::default().with_strength(Action::Inflate, 1.0);
+    for _ in 0..seconds(3.0) {
+        balloon.step(&controls, &mut body, &mut air, None, &[], SIM_TICK);
+    }
+    assert!(balloon.pops_on(SHARP_TILE));
+    assert!(!balloon.pops_on(1));
+}
+
+#[test]
+fn inflating_into_sharp_tiles_pops_at_once() {
+    let first_step_bursts = |wall: u16, inflation: f32| {
+        let mut balloon = Balloon::default();
+        let mut body = Body {
+            pos: Vec2::new(48.0, 48.0),
+            ..Default::default()
+        };
+        balloon.set_inflation_rate(inflation, &mut body);
+        let mut air = AirTank::full(1.0);
+        let controls = Controls::default().with_strength(Action::Inflate, 1.0);
+        balloon
+            .step(
+                &controls,
+                &mut body,
+                &mut air,
+                Some(&boxed_in(wall)),
+                &[],
+                SIM_TICK,
+            )
+            .burst
+    };
+    assert!(first_step_bursts(SHARP_TILE, 6.0));
+    assert!(!first_step_bursts(SHARP_TILE, 3.0));
+    assert!(!first_step_bursts(1, 6.0));
+}
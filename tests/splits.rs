@@ -0,0 +1,26 @@
+use balloon_game::splits::Splits;
+
+fn splits(segments: &[(&str, f32)]) -> Splits {
+    let mut splits = Splits::default();
+    for (level, segment) in segments {
+        splits.push(level.to_string(), *segment);
+    }
+    splits
+}
+
+#[test]
+fn delta_compares_the_levels_of_the_run() {
+    let pb = splits(&[("Level_0", 10.0), ("Level_1", 20.0)]);
+    let run = splits(&[("Level_0", 8.0), ("Level_1", 21.0)]);
+    assert_eq!(run.delta(&pb), Some(-1.0));
+    assert_eq!(splits(&[("Level_2", 5.0)]).delta(&pb), None);
+}
+
+#[test]
+fn only_full_runs_become_the_personal_best() {
+    let pb = splits(&[("Level_0", 10.0), ("Level_1", 20.0)]);
+    assert!(splits(&[("Level_0", 10.0), ("Level_1", 19.0)]).beats(Some(&pb), "Level_0"));
+    assert!(!splits(&[("Level_0", 10.0), ("Level_1", 21.0)]).beats(Some(&pb), "Level_0"));
+    assert!(!splits(&[("Level_1", 1.0)]).beats(Some(&pb), "Level_0"));
+    assert!(splits(&[("Level_0", 30.0)]).beats(None, "Level_0"));
+}
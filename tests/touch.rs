@@ -0,0 +1,51 @@
+use balloon_game::{
+    touch::{logical_size, screen_scale, TouchLayout},
+    Action, Controls,
+};
+use roast_2d::prelude::Vec2;
+
+fn layout() -> TouchLayout {
+    TouchLayout::new(Vec2::new(512.0, 512.0))
+}
+
+#[test]
+fn dpad_presses_the_directions_of_the_touch() {
+    let layout = layout();
+    let center = layout.dpad_center;
+    let radius = layout.dpad_radius;
+    assert!(layout.pressed(&[center]).is_empty());
+    assert_eq!(
+        layout.pressed(&[center + Vec2::new(radius, 0.0) * 0.8]),
+        [Action::Right]
+    );
+    assert_eq!(
+        layout.pressed(&[center + Vec2::new(-0.5, -0.5) * radius]),
+        [Action::Left, Action::Up]
+    );
+}
+
+#[test]
+fn every_finger_presses_its_button() {
+    let layout = layout();
+    let [jump, inflate, ..] = layout.buttons;
+    let touches = [jump.center, inflate.center, Vec2::new(256.0, 256.0)];
+    assert_eq!(layout.pressed(&touches), [Action::Jump, Action::Inflate]);
+
+    let first = layout.controls(&touches, &Controls::default());
+    assert!(first.just_pressed(Action::Jump));
+    let held = layout.controls(&touches, &first);
+    assert!(held.pressed(Action::Jump) && !held.just_pressed(Action::Jump));
+}
+
+#[test]
+fn portrait_screens_keep_the_dpad_at_the_bottom() {
+    let view = Vec2::new(512.0, 512.0);
+    let window = Vec2::new(390.0, 844.0);
+    assert_eq!(screen_scale(window, view), 390.0 / 512.0);
+    let screen = logical_size(window, view);
+    assert_eq!(screen.x, 512.0);
+    assert!(screen.y > 1100.0);
+    let layout = TouchLayout::new(screen);
+    assert!(layout.dpad_center.y > 1000.0);
+    assert!(layout.buttons[0].center.y > 1000.0);
+}
@@ -0,0 +1,53 @@
+use std::fs;
+
+use balloon_game::{
+    sim::{load_project, Sim, SimState},
+    tuning::Tuning,
+    Action, Controls,
+};
+use roast_2d::ldtk::LdtkLevel;
+use serde_json::json;
+
+#[test]
+fn tuning_file_matches_the_defaults() {
+    let data = fs::read("assets/tuning.json").unwrap();
+    assert_eq!(Tuning::from_slice(&data).unwrap(), Tuning::default());
+}
+
+#[test]
+fn level_fields_override_the_tuning() {
+    let level: LdtkLevel = serde_json::from_value(json!({
+        "identifier": "Level_0",
+        "iid": "level-0",
+        "uid": 0,
+        "bgRelPath": null,
+        "bgColor": null,
+        "fieldInstances": [
+            { "__identifier": "gravity", "__type": "Float", "__value": 100.0, "defUid": 1 },
+            { "__identifier": "Music", "__type": "String", "__value": "theme", "defUid": 2 }
+        ],
+        "layerInstances": []
+    }))
+    .unwrap();
+    let tuning = Tuning::default().for_level(&level);
+    assert_eq!(tuning.gravity, 100.0);
+    assert_eq!(tuning.jump_vel, Tuning::default().jump_vel);
+}
+
+#[test]
+fn weaker_jumps_miss_the_wall() {
+    let proj = load_project("assets/game.ldtk").unwrap();
+    let tuning = Tuning {
+        jump_vel: 100.0,
+        ..Default::default()
+    };
+    let mut sim = Sim::with_tuning(&proj, "Level_0", &tuning).unwrap();
+    let state = sim.run(300, |frame| {
+        if frame % 60 < 10 {
+            Controls::holding(&[Action::Right, Action::Jump])
+        } else {
+            Controls::holding(&[Action::Right])
+        }
+    });
+    assert_eq!(state, SimState::Running);
+}